@@ -1,6 +1,9 @@
 use crate::app_builder::AppBuilder;
 use bevy_ecs::{ParallelExecutor, Resources, Schedule, World};
 
+/// A callback that runs when an [App] exits. See [App::shutdown]
+pub type ExitHandler = dyn Fn(&Resources);
+
 /// Containers of app logic and data
 ///
 /// App store the ECS World, Resources, Schedule, and Executor. They also store the "run" function of the App, which
@@ -30,6 +33,8 @@ pub struct App {
     pub executor: ParallelExecutor,
     pub startup_schedule: Schedule,
     pub startup_executor: ParallelExecutor,
+    /// Callbacks run by [App::shutdown], in the order they were added
+    pub exit_handlers: Vec<Box<ExitHandler>>,
 }
 
 impl Default for App {
@@ -42,6 +47,7 @@ impl Default for App {
             startup_schedule: Default::default(),
             startup_executor: ParallelExecutor::without_tracker_clears(),
             runner: Box::new(run_once),
+            exit_handlers: Vec::new(),
         }
     }
 }
@@ -61,13 +67,26 @@ impl App {
             .run(&mut self.schedule, &mut self.world, &mut self.resources);
     }
 
-    pub fn run(mut self) {
+    /// Runs the startup schedule. [App::run] calls this before handing the app to its runner.
+    pub fn startup(&mut self) {
         self.startup_schedule.initialize(&mut self.resources);
         self.startup_executor.run(
             &mut self.startup_schedule,
             &mut self.world,
             &mut self.resources,
         );
+    }
+
+    /// Runs the app's exit handlers, which stop background work (ex: asset loading) before the app is dropped.
+    /// Runners call this once they stop updating.
+    pub fn shutdown(&mut self) {
+        for exit_handler in self.exit_handlers.iter() {
+            exit_handler(&self.resources);
+        }
+    }
+
+    pub fn run(mut self) {
+        self.startup();
 
         let runner = std::mem::replace(&mut self.runner, Box::new(run_once));
         (runner)(self);
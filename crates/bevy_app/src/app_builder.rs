@@ -1,20 +1,27 @@
 use crate::{
     app::{App, AppExit},
+    command_queue::{command_queue_system, CommandQueue},
     event::Events,
     plugin::{dynamically_load_plugin, Plugin},
+    rng::AppRng,
     stage, startup_stage,
 };
-use bevy_ecs::{FromResources, IntoQuerySystem, Resources, System, World};
+use bevy_ecs::{
+    FromResources, IntoQuerySystem, IntoThreadLocalSystem, PreviousValue, ResMut, Resources,
+    System, World,
+};
 
 /// Configure [App]s using the builder pattern
 pub struct AppBuilder {
     pub app: App,
+    startup_ran: bool,
 }
 
 impl Default for AppBuilder {
     fn default() -> Self {
         let mut app_builder = AppBuilder {
             app: App::default(),
+            startup_ran: false,
         };
 
         app_builder.add_default_stages();
@@ -27,6 +34,7 @@ impl AppBuilder {
     pub fn empty() -> AppBuilder {
         AppBuilder {
             app: App::default(),
+            startup_ran: false,
         }
     }
 
@@ -43,6 +51,25 @@ impl AppBuilder {
         app.run();
     }
 
+    /// Runs the app's schedule once and returns, running the startup schedule first if it hasn't run yet. Unlike
+    /// [AppBuilder::run], this ignores the runner and keeps the app around, so tests can tick a fully built app
+    /// deterministically.
+    pub fn update(&mut self) -> &mut Self {
+        if !self.startup_ran {
+            self.app.startup();
+            self.startup_ran = true;
+        }
+
+        self.app.update();
+        self
+    }
+
+    /// Runs the startup schedule (if it hasn't run yet) and a single update, ignoring the app's runner. Unlike
+    /// [AppBuilder::run], the app is kept intact afterwards, so its world and resources can be inspected.
+    pub fn run_once(&mut self) -> &mut Self {
+        self.update()
+    }
+
     pub fn set_world(&mut self, world: World) -> &mut Self {
         self.app.world = world;
         self
@@ -197,6 +224,20 @@ impl AppBuilder {
             .add_system_to_stage(stage::EVENT_UPDATE, Events::<T>::update_system.system())
     }
 
+    /// Like [AppBuilder::add_event], but events stay readable for exactly `frames` updates after they are sent instead of
+    /// one. See [Events::update_with_lifetime].
+    pub fn add_event_with_lifetime<T>(&mut self, frames: u32) -> &mut Self
+    where
+        T: Send + Sync + 'static,
+    {
+        assert!(frames > 0, "event lifetime must be at least one frame");
+        let update_system = move |mut events: ResMut<Events<T>>| {
+            events.update_with_lifetime(frames);
+        };
+        self.add_resource(Events::<T>::default())
+            .add_system_to_stage(stage::EVENT_UPDATE, update_system.system())
+    }
+
     pub fn add_resource<T>(&mut self, resource: T) -> &mut Self
     where
         T: Send + Sync + 'static,
@@ -205,6 +246,34 @@ impl AppBuilder {
         self
     }
 
+    /// Adds a [CommandQueue] resource, which code outside of systems can push commands to. Queued commands are applied
+    /// at the start of every update.
+    pub fn add_command_queue(&mut self) -> &mut Self {
+        self.init_resource::<CommandQueue>()
+            .add_system_to_stage(stage::FIRST, command_queue_system.thread_local_system())
+    }
+
+    /// Tracks the value `T` had at the end of the previous frame, so systems can read it through a
+    /// [ResHistory](bevy_ecs::ResHistory) parameter. `T` must already be added.
+    pub fn add_resource_history<T>(&mut self) -> &mut Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let previous = self
+            .resources()
+            .get::<T>()
+            .map(|current| (*current).clone())
+            .expect("Resource history requires the resource to be added first.");
+        self.add_resource(PreviousValue::new(previous))
+            .add_system_to_stage(stage::LAST, PreviousValue::<T>::update_system.system())
+    }
+
+    /// Adds an [AppRng] resource seeded with `seed`, so systems that draw random numbers from it produce the same
+    /// sequence on every run
+    pub fn set_rng_seed(&mut self, seed: u64) -> &mut Self {
+        self.add_resource(AppRng::from_seed(seed))
+    }
+
     pub fn init_resource<R>(&mut self) -> &mut Self
     where
         R: FromResources + Send + Sync + 'static,
@@ -215,6 +284,26 @@ impl AppBuilder {
         self
     }
 
+    /// Initializes `R` like [AppBuilder::init_resource], first initializing `Dep` if it hasn't been added yet. Use this
+    /// when `R`'s [FromResources] impl reads `Dep`, so `R` is never built before its dependency exists.
+    pub fn init_resource_after<R, Dep>(&mut self) -> &mut Self
+    where
+        R: FromResources + Send + Sync + 'static,
+        Dep: FromResources + Send + Sync + 'static,
+    {
+        if !self.app.resources.contains::<Dep>() {
+            self.init_resource::<Dep>();
+        }
+
+        self.init_resource::<R>()
+    }
+
+    /// Adds a callback that runs when the app's runner exits (ex: after an [AppExit] event). See [App::shutdown].
+    pub fn add_exit_handler(&mut self, exit_handler: impl Fn(&Resources) + 'static) -> &mut Self {
+        self.app.exit_handlers.push(Box::new(exit_handler));
+        self
+    }
+
     pub fn set_runner(&mut self, run_fn: impl Fn(App) + 'static) -> &mut Self {
         self.app.runner = Box::new(run_fn);
         self
@@ -236,3 +325,194 @@ impl AppBuilder {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{App, AppBuilder};
+    use crate::{event::Events, plugin::Plugin};
+    use bevy_ecs::{FromResources, IntoQuerySystem, Res, ResMut, Resources, SystemOrdering};
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        thread,
+        time::Duration,
+    };
+
+    struct TestEvent;
+
+    #[test]
+    fn event_lifetime() {
+        let mut app_builder = AppBuilder::default();
+        app_builder.add_event_with_lifetime::<TestEvent>(3);
+        let app = &mut app_builder.app;
+        app.schedule.initialize(&mut app.resources);
+
+        app.resources
+            .get_mut::<Events<TestEvent>>()
+            .unwrap()
+            .send(TestEvent);
+
+        let event_count = |app: &App| {
+            let events = app.resources.get::<Events<TestEvent>>().unwrap();
+            events.get_reader().iter(&events).count()
+        };
+
+        for update in 1..=6 {
+            app.schedule.run(&mut app.world, &mut app.resources);
+            assert_eq!(
+                event_count(app),
+                if update <= 3 { 1 } else { 0 },
+                "the event is readable for exactly 3 updates (checked after update {})",
+                update
+            );
+        }
+    }
+
+    struct Dep(u32);
+
+    impl Default for Dep {
+        fn default() -> Self {
+            Dep(3)
+        }
+    }
+
+    struct Dependent(u32);
+
+    impl FromResources for Dependent {
+        fn from_resources(resources: &Resources) -> Self {
+            Dependent(resources.get::<Dep>().unwrap().0 * 2)
+        }
+    }
+
+    #[test]
+    fn init_resource_after_dependency() {
+        let mut app_builder = AppBuilder::default();
+        app_builder.init_resource_after::<Dependent, Dep>();
+        assert_eq!(app_builder.resources().get::<Dep>().unwrap().0, 3);
+        assert_eq!(app_builder.resources().get::<Dependent>().unwrap().0, 6);
+
+        let mut app_builder = AppBuilder::default();
+        app_builder
+            .add_resource(Dep(5))
+            .init_resource_after::<Dependent, Dep>();
+        assert_eq!(
+            app_builder.resources().get::<Dep>().unwrap().0,
+            5,
+            "an existing dependency is not replaced"
+        );
+        assert_eq!(app_builder.resources().get::<Dependent>().unwrap().0, 10);
+    }
+
+    #[test]
+    fn update_runs_schedule_once_per_call() {
+        fn count(mut counter: ResMut<usize>) {
+            *counter += 1;
+        }
+
+        fn startup(mut startup_runs: ResMut<Vec<usize>>) {
+            startup_runs.push(0);
+        }
+
+        let mut app_builder = App::build();
+        app_builder
+            .add_resource(0usize)
+            .add_resource(Vec::<usize>::new())
+            .add_startup_system(startup.system())
+            .add_system(count.system());
+
+        app_builder.update().update().update();
+        assert_eq!(*app_builder.resources().get::<usize>().unwrap(), 3);
+        assert_eq!(
+            app_builder.resources().get::<Vec<usize>>().unwrap().len(),
+            1,
+            "startup systems only run before the first update"
+        );
+    }
+
+    #[test]
+    fn run_once_runs_startup_and_one_update() {
+        let startup_runs = Arc::new(AtomicUsize::new(0));
+        let update_runs = Arc::new(AtomicUsize::new(0));
+        let startup = {
+            let startup_runs = startup_runs.clone();
+            move || {
+                startup_runs.fetch_add(1, Ordering::SeqCst);
+            }
+        };
+        let count = {
+            let update_runs = update_runs.clone();
+            move || {
+                update_runs.fetch_add(1, Ordering::SeqCst);
+            }
+        };
+
+        let mut app_builder = App::build();
+        app_builder
+            .add_resource(0usize)
+            .add_startup_system(startup.system())
+            .add_system(count.system());
+        app_builder.run_once();
+
+        assert_eq!(startup_runs.load(Ordering::SeqCst), 1);
+        assert_eq!(update_runs.load(Ordering::SeqCst), 1);
+        assert!(
+            app_builder.resources().contains::<usize>(),
+            "the app is kept intact"
+        );
+
+        app_builder.update();
+        assert_eq!(
+            startup_runs.load(Ordering::SeqCst),
+            1,
+            "startup only runs once"
+        );
+        assert_eq!(update_runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn systems_ordered_by_label_across_plugins() {
+        type Log = Arc<Mutex<Vec<&'static str>>>;
+
+        fn physics(log: Res<Log>) {
+            // give render a chance to run first if it isn't ordered after physics
+            thread::sleep(Duration::from_millis(10));
+            log.lock().unwrap().push("physics");
+        }
+
+        fn render(log: Res<Log>) {
+            log.lock().unwrap().push("render");
+        }
+
+        struct PhysicsPlugin;
+
+        impl Plugin for PhysicsPlugin {
+            fn build(&self, app: &mut AppBuilder) {
+                app.add_system(physics.system().label("physics"));
+            }
+        }
+
+        struct RenderPlugin;
+
+        impl Plugin for RenderPlugin {
+            fn build(&self, app: &mut AppBuilder) {
+                app.add_system(render.system().after("physics"));
+            }
+        }
+
+        let log = Log::default();
+        let mut app_builder = AppBuilder::default();
+        app_builder
+            .add_resource(log.clone())
+            .add_plugin(RenderPlugin)
+            .add_plugin(PhysicsPlugin)
+            .update()
+            .update();
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["physics", "render", "physics", "render"]
+        );
+    }
+}
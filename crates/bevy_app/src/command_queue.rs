@@ -0,0 +1,53 @@
+use bevy_ecs::{Commands, Resources, World};
+
+/// A queue of world and resource mutations that code outside of systems (ex: an input callback on another thread)
+/// can push to. Clones share the same queue. [command_queue_system] applies the queued commands once per update.
+#[derive(Default, Clone)]
+pub struct CommandQueue {
+    commands: Commands,
+}
+
+impl CommandQueue {
+    /// Queues the commands added by `f`. They are applied the next time [command_queue_system] runs.
+    pub fn push(&self, f: impl FnOnce(&mut Commands)) {
+        f(&mut self.commands.clone());
+    }
+}
+
+/// Applies the commands queued in the [CommandQueue] resource
+pub fn command_queue_system(world: &mut World, resources: &mut Resources) {
+    let command_queue = (*resources.get::<CommandQueue>().unwrap()).clone();
+    command_queue.commands.apply(world, resources);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CommandQueue;
+    use crate::AppBuilder;
+    use std::thread;
+
+    struct Spawned;
+
+    #[test]
+    fn apply_commands_pushed_from_another_thread() {
+        let mut app_builder = AppBuilder::default();
+        app_builder.add_command_queue();
+        let command_queue = (*app_builder.resources().get::<CommandQueue>().unwrap()).clone();
+
+        thread::spawn(move || {
+            command_queue.push(|commands| {
+                commands.spawn((Spawned,));
+            });
+        })
+        .join()
+        .unwrap();
+
+        let count =
+            |app_builder: &AppBuilder| app_builder.app.world.query::<&Spawned>().iter().count();
+        assert_eq!(count(&app_builder), 0);
+        app_builder.update();
+        assert_eq!(count(&app_builder), 1);
+        app_builder.update();
+        assert_eq!(count(&app_builder), 1, "commands are only applied once");
+    }
+}
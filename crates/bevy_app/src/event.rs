@@ -1,9 +1,10 @@
-use bevy_ecs::ResMut;
-use std::marker::PhantomData;
+use bevy_ecs::{Res, ResMut};
+use std::{collections::VecDeque, marker::PhantomData};
 
 #[derive(Debug)]
 struct EventInstance<T> {
     pub event_count: usize,
+    pub update_count: usize,
     pub event: T,
 }
 
@@ -51,7 +52,8 @@ enum State {
 /// [EventReader]s that read at least once per update will never drop events. [EventReader]s that read once within two updates might
 /// still receive some events. [EventReader]s that read after two updates are guaranteed to drop all events that occurred before those updates.
 ///
-/// The buffers in [Events] will grow indefinitely if [Events::update] is never called.
+/// The buffers in [Events] will grow indefinitely if [Events::update] is never called, unless the collection was created with
+/// [Events::with_capacity_limit].
 ///
 /// An alternative call pattern would be to call [Events::update] manually across frames to control when events are cleared. However
 /// this complicates consumption
@@ -62,7 +64,11 @@ pub struct Events<T> {
     a_start_event_count: usize,
     b_start_event_count: usize,
     event_count: usize,
+    update_count: usize,
     state: State,
+    capacity_limit: Option<usize>,
+    dropped_event_count: usize,
+    warned_about_drops: bool,
 }
 
 impl<T> Default for Events<T> {
@@ -71,13 +77,21 @@ impl<T> Default for Events<T> {
             a_start_event_count: 0,
             b_start_event_count: 0,
             event_count: 0,
+            update_count: 0,
             events_a: Vec::new(),
             events_b: Vec::new(),
             state: State::A,
+            capacity_limit: None,
+            dropped_event_count: 0,
+            warned_about_drops: false,
         }
     }
 }
 
+/// A monotonically increasing id assigned to each event sent to an [Events] collection
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct EventId(pub usize);
+
 fn map_instance_event<T>(event_instance: &EventInstance<T>) -> &T {
     &event_instance.event
 }
@@ -101,6 +115,23 @@ impl<T> EventReader<T> {
     /// Iterates over the events this EventReader has not seen yet. This updates the EventReader's
     /// event counter, which means subsequent event reads will not include events that happened before now.
     pub fn iter<'a>(&mut self, events: &'a Events<T>) -> impl DoubleEndedIterator<Item = &'a T> {
+        self.iter_instances(events).map(map_instance_event)
+    }
+
+    /// Like [EventReader::iter], but also yields each event's [EventId]. Ids are assigned by [Events] in the order events
+    /// are sent, so they can be used to correlate events across readers.
+    pub fn iter_with_id<'a>(
+        &mut self,
+        events: &'a Events<T>,
+    ) -> impl DoubleEndedIterator<Item = (EventId, &'a T)> {
+        self.iter_instances(events)
+            .map(|event_instance| (EventId(event_instance.event_count), &event_instance.event))
+    }
+
+    fn iter_instances<'a>(
+        &mut self,
+        events: &'a Events<T>,
+    ) -> impl DoubleEndedIterator<Item = &'a EventInstance<T>> {
         // if the reader has seen some of the events in a buffer, find the proper index offset.
         // otherwise read all events in the buffer
         let a_index = if self.last_event_count > events.a_start_event_count {
@@ -120,29 +151,13 @@ impl<T> EventReader<T> {
                 .get(b_index..)
                 .unwrap_or_else(|| &[])
                 .iter()
-                .map(map_instance_event)
-                .chain(
-                    events
-                        .events_a
-                        .get(a_index..)
-                        .unwrap_or_else(|| &[])
-                        .iter()
-                        .map(map_instance_event),
-                ),
+                .chain(events.events_a.get(a_index..).unwrap_or_else(|| &[]).iter()),
             State::B => events
                 .events_a
                 .get(a_index..)
                 .unwrap_or_else(|| &[])
                 .iter()
-                .map(map_instance_event)
-                .chain(
-                    events
-                        .events_b
-                        .get(b_index..)
-                        .unwrap_or_else(|| &[])
-                        .iter()
-                        .map(map_instance_event),
-                ),
+                .chain(events.events_b.get(b_index..).unwrap_or_else(|| &[]).iter()),
         }
     }
 
@@ -170,11 +185,22 @@ impl<T> EventReader<T> {
 }
 
 impl<T: bevy_ecs::Resource> Events<T> {
+    /// Creates an [Events] collection that holds at most `limit` events per update. Once the limit is reached, sending
+    /// an event drops the oldest event sent since the last [Events::update]. Use this for event types that may be
+    /// produced faster than they are read (or never read at all).
+    pub fn with_capacity_limit(limit: usize) -> Self {
+        Events {
+            capacity_limit: Some(limit),
+            ..Default::default()
+        }
+    }
+
     /// "Sends" an `event` by writing it to the current event buffer. [EventReader]s can then read the event.
     pub fn send(&mut self, event: T) {
         let event_instance = EventInstance {
             event,
             event_count: self.event_count,
+            update_count: self.update_count,
         };
 
         match self.state {
@@ -183,6 +209,34 @@ impl<T: bevy_ecs::Resource> Events<T> {
         }
 
         self.event_count += 1;
+
+        if let Some(limit) = self.capacity_limit {
+            let (events, start_event_count) = match self.state {
+                State::A => (&mut self.events_a, &mut self.a_start_event_count),
+                State::B => (&mut self.events_b, &mut self.b_start_event_count),
+            };
+            if events.len() > limit {
+                let dropped = events.len() - limit;
+                events.drain(..dropped);
+                // readers index into the buffer relative to its start, so it has to move with the dropped events
+                *start_event_count += dropped;
+                self.dropped_event_count += dropped;
+                // warn once per update, so a flood of events doesn't also flood the log
+                if !self.warned_about_drops {
+                    self.warned_about_drops = true;
+                    log::warn!(
+                        "Dropping {} events after exceeding the capacity limit of {}",
+                        std::any::type_name::<T>(),
+                        limit
+                    );
+                }
+            }
+        }
+    }
+
+    /// The total number of events dropped because they exceeded the capacity limit. See [Events::with_capacity_limit]
+    pub fn dropped_event_count(&self) -> usize {
+        self.dropped_event_count
     }
 
     /// Gets a new [EventReader]. This will include all events already in the event buffers.
@@ -203,6 +257,8 @@ impl<T: bevy_ecs::Resource> Events<T> {
 
     /// Swaps the event buffers and clears the oldest event buffer. In general, this should be called once per frame/update.
     pub fn update(&mut self) {
+        self.update_count += 1;
+        self.warned_about_drops = false;
         match self.state {
             State::A => {
                 self.events_b = Vec::new();
@@ -217,6 +273,43 @@ impl<T: bevy_ecs::Resource> Events<T> {
         }
     }
 
+    /// Like [Events::update], but keeps each event readable for exactly `frames` updates after the update it was sent in,
+    /// instead of dropping it after the next buffer swap. `update_with_lifetime(1)` behaves like [Events::update]. Every
+    /// call should use the same `frames`.
+    pub fn update_with_lifetime(&mut self, frames: u32) {
+        self.update_count += 1;
+        self.warned_about_drops = false;
+        let (older, older_start_event_count, newer, newer_start_event_count) = match self.state {
+            State::A => (
+                &mut self.events_b,
+                &mut self.b_start_event_count,
+                &mut self.events_a,
+                &mut self.a_start_event_count,
+            ),
+            State::B => (
+                &mut self.events_a,
+                &mut self.a_start_event_count,
+                &mut self.events_b,
+                &mut self.b_start_event_count,
+            ),
+        };
+
+        // the newer buffer only holds events sent since the last update, so move them to the older buffer and
+        // expire events from its front. event counts stay contiguous, so readers can keep indexing relative to the
+        // buffer starts
+        older.append(newer);
+        *newer_start_event_count = self.event_count;
+        let update_count = self.update_count;
+        let expired = older
+            .iter()
+            .take_while(|event_instance| {
+                update_count - event_instance.update_count > frames as usize
+            })
+            .count();
+        older.drain(..expired);
+        *older_start_event_count += expired;
+    }
+
     /// A system that calls [Events::update] once per frame.
     pub fn update_system(mut events: ResMut<Self>) {
         events.update();
@@ -265,6 +358,103 @@ impl<T: bevy_ecs::Resource> Events<T> {
             State::B => self.events_b.iter().map(map_instance_event),
         }
     }
+
+    /// Clones every event currently buffered, oldest first, without advancing any [EventReader]. This lets code outside
+    /// of systems (ex: a logger on another thread) observe events. Every call clones all buffered events.
+    pub fn snapshot(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let (older, newer) = match self.state {
+            State::A => (&self.events_b, &self.events_a),
+            State::B => (&self.events_a, &self.events_b),
+        };
+        older
+            .iter()
+            .chain(newer.iter())
+            .map(|event_instance| event_instance.event.clone())
+            .collect()
+    }
+}
+
+/// Captures every event sent to an [Events] collection, along with the frame it was read on. Recording uses its own
+/// [EventReader], so it does not interfere with other readers. [EventRecorder::record] should be called exactly once per
+/// frame/update. [EventRecorder::record_system] is a system that does this.
+pub struct EventRecorder<T> {
+    reader: EventReader<T>,
+    frame: usize,
+    recorded: Vec<(usize, T)>,
+}
+
+impl<T> Default for EventRecorder<T> {
+    fn default() -> Self {
+        Self {
+            reader: EventReader::default(),
+            frame: 0,
+            recorded: Vec::new(),
+        }
+    }
+}
+
+impl<T: bevy_ecs::Resource + Clone> EventRecorder<T> {
+    /// Records the events sent since the last call and advances to the next frame
+    pub fn record(&mut self, events: &Events<T>) {
+        let frame = self.frame;
+        self.recorded
+            .extend(self.reader.iter(events).map(|event| (frame, event.clone())));
+        self.frame += 1;
+    }
+
+    /// A system that calls [EventRecorder::record] once per frame.
+    pub fn record_system(mut recorder: ResMut<Self>, events: Res<Events<T>>) {
+        recorder.record(&events);
+    }
+
+    /// The recorded events in the order they were sent, paired with the frame they were recorded on
+    pub fn recorded(&self) -> &[(usize, T)] {
+        &self.recorded
+    }
+
+    /// Consumes the recorder, returning an [EventPlayer] that re-sends the recorded events on the same frames
+    pub fn into_player(self) -> EventPlayer<T> {
+        EventPlayer {
+            frame: 0,
+            events: self.recorded.into_iter().collect(),
+        }
+    }
+}
+
+/// Re-sends previously recorded events to an [Events] collection on the frames they were recorded on.
+/// [EventPlayer::play] should be called exactly once per frame/update. [EventPlayer::play_system] is a system that does this.
+pub struct EventPlayer<T> {
+    frame: usize,
+    events: VecDeque<(usize, T)>,
+}
+
+impl<T: bevy_ecs::Resource> EventPlayer<T> {
+    /// Sends the events recorded on the current frame and advances to the next frame
+    pub fn play(&mut self, events: &mut Events<T>) {
+        while let Some((frame, _)) = self.events.front() {
+            if *frame > self.frame {
+                break;
+            }
+
+            let (_, event) = self.events.pop_front().unwrap();
+            events.send(event);
+        }
+
+        self.frame += 1;
+    }
+
+    /// A system that calls [EventPlayer::play] once per frame.
+    pub fn play_system(mut player: ResMut<Self>, mut events: ResMut<Events<T>>) {
+        player.play(&mut events);
+    }
+
+    /// Returns true if every recorded event has been sent
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
 }
 
 #[cfg(test)]
@@ -366,10 +556,181 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_event_ids() {
+        let mut events = Events::<TestEvent>::default();
+        let mut reader = events.get_reader();
+
+        events.send(TestEvent { i: 0 });
+        events.send(TestEvent { i: 1 });
+        let first_ids = reader
+            .iter_with_id(&events)
+            .map(|(id, _)| id)
+            .collect::<Vec<EventId>>();
+
+        events.update();
+        events.send(TestEvent { i: 2 });
+
+        let mut late_reader = events.get_reader();
+        let all_ids = late_reader
+            .iter_with_id(&events)
+            .map(|(id, event)| (id, event.i))
+            .collect::<Vec<(EventId, usize)>>();
+        assert_eq!(
+            all_ids,
+            vec![(EventId(0), 0), (EventId(1), 1), (EventId(2), 2)],
+            "ids are strictly increasing and stable across the buffer swap"
+        );
+
+        assert_eq!(first_ids, vec![EventId(0), EventId(1)]);
+        assert_eq!(
+            reader
+                .iter_with_id(&events)
+                .map(|(id, _)| id)
+                .collect::<Vec<EventId>>(),
+            vec![EventId(2)],
+            "readers agree on the id of the same event"
+        );
+    }
+
+    #[test]
+    fn test_event_replay() {
+        // runs three frames, calling `send` on each one, and returns the events a reader handled per frame
+        fn run_frames(
+            events: &mut Events<TestEvent>,
+            mut send: impl FnMut(usize, &mut Events<TestEvent>),
+            mut recorder: Option<&mut EventRecorder<TestEvent>>,
+        ) -> Vec<Vec<TestEvent>> {
+            let mut reader = events.get_reader();
+            let mut handled = Vec::new();
+            for frame in 0..3 {
+                events.update();
+                send(frame, events);
+                if let Some(recorder) = recorder.as_mut() {
+                    recorder.record(events);
+                }
+                handled.push(get_events(events, &mut reader));
+            }
+            handled
+        }
+
+        let mut recorder = EventRecorder::<TestEvent>::default();
+        let recorded_handled = run_frames(
+            &mut Events::default(),
+            |frame, events| {
+                if frame != 1 {
+                    events.send(TestEvent { i: frame * 2 });
+                    events.send(TestEvent { i: frame * 2 + 1 });
+                }
+            },
+            Some(&mut recorder),
+        );
+        assert_eq!(
+            recorder.recorded(),
+            &[
+                (0, TestEvent { i: 0 }),
+                (0, TestEvent { i: 1 }),
+                (2, TestEvent { i: 4 }),
+                (2, TestEvent { i: 5 })
+            ]
+        );
+
+        let mut player = recorder.into_player();
+        let replayed_handled = run_frames(
+            &mut Events::default(),
+            |_, events| player.play(events),
+            None,
+        );
+        assert_eq!(replayed_handled, recorded_handled);
+        assert!(player.is_finished());
+    }
+
     fn get_events(
         events: &Events<TestEvent>,
         reader: &mut EventReader<TestEvent>,
     ) -> Vec<TestEvent> {
         reader.iter(events).cloned().collect::<Vec<TestEvent>>()
     }
+
+    #[test]
+    fn test_event_capacity_limit() {
+        let mut events = Events::<TestEvent>::with_capacity_limit(3);
+        let mut reader = events.get_reader();
+
+        for i in 0..5 {
+            events.send(TestEvent { i });
+        }
+        assert_eq!(
+            reader.iter(&events).cloned().collect::<Vec<_>>(),
+            vec![TestEvent { i: 2 }, TestEvent { i: 3 }, TestEvent { i: 4 }],
+            "only the newest events are retained"
+        );
+        assert_eq!(events.dropped_event_count(), 2);
+
+        events.update();
+        for i in 5..9 {
+            events.send(TestEvent { i });
+        }
+        assert_eq!(
+            reader.iter(&events).cloned().collect::<Vec<_>>(),
+            vec![TestEvent { i: 6 }, TestEvent { i: 7 }, TestEvent { i: 8 }],
+            "the limit applies per update"
+        );
+        assert_eq!(events.get_reader().iter(&events).count(), 6);
+        assert_eq!(events.dropped_event_count(), 3);
+    }
+
+    #[test]
+    fn test_event_lifetime() {
+        let mut events = Events::<TestEvent>::default();
+        let mut reader = events.get_reader();
+
+        events.send(TestEvent { i: 0 });
+        events.update_with_lifetime(2);
+        events.send(TestEvent { i: 1 });
+        assert_eq!(
+            reader.iter(&events).cloned().collect::<Vec<_>>(),
+            vec![TestEvent { i: 0 }, TestEvent { i: 1 }]
+        );
+
+        events.update_with_lifetime(2);
+        events.send(TestEvent { i: 2 });
+        assert_eq!(
+            reader.iter(&events).cloned().collect::<Vec<_>>(),
+            vec![TestEvent { i: 2 }],
+            "readers do not see events twice"
+        );
+        assert_eq!(
+            events.snapshot(),
+            vec![TestEvent { i: 0 }, TestEvent { i: 1 }, TestEvent { i: 2 }]
+        );
+
+        events.update_with_lifetime(2);
+        assert_eq!(
+            events.snapshot(),
+            vec![TestEvent { i: 1 }, TestEvent { i: 2 }],
+            "events expire after 2 updates"
+        );
+        assert_eq!(events.get_reader().iter(&events).count(), 2);
+    }
+
+    #[test]
+    fn test_event_snapshot() {
+        let mut events = Events::<TestEvent>::default();
+        let mut reader = events.get_reader();
+
+        events.send(TestEvent { i: 0 });
+        events.update();
+        events.send(TestEvent { i: 1 });
+
+        assert_eq!(
+            events.snapshot(),
+            vec![TestEvent { i: 0 }, TestEvent { i: 1 }]
+        );
+        assert_eq!(
+            reader.iter(&events).cloned().collect::<Vec<_>>(),
+            vec![TestEvent { i: 0 }, TestEvent { i: 1 }],
+            "taking a snapshot does not consume events"
+        );
+    }
 }
@@ -5,15 +5,19 @@ pub mod startup_stage;
 
 mod app;
 mod app_builder;
+mod command_queue;
 mod event;
 mod plugin;
+mod rng;
 mod schedule_runner;
 
 pub use app::*;
 pub use app_builder::*;
 pub use bevy_derive::DynamicPlugin;
+pub use command_queue::*;
 pub use event::*;
 pub use plugin::*;
+pub use rng::*;
 pub use schedule_runner::*;
 
 pub mod prelude {
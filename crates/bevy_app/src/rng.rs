@@ -0,0 +1,67 @@
+use rand::{rngs::StdRng, Error, RngCore, SeedableRng};
+
+/// A seeded random number generator resource. Systems that take `ResMut<AppRng>` draw from a single reproducible
+/// sequence, which keeps tests and replays deterministic. Add it with [AppBuilder::set_rng_seed](crate::AppBuilder::set_rng_seed).
+pub struct AppRng {
+    rng: StdRng,
+}
+
+impl AppRng {
+    pub fn from_seed(seed: u64) -> Self {
+        AppRng {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl RngCore for AppRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::App;
+    use bevy_ecs::{IntoQuerySystem, ResMut};
+    use rand::Rng;
+
+    fn roll(mut rng: ResMut<super::AppRng>, mut rolls: ResMut<Vec<u32>>) {
+        rolls.push(rng.gen());
+    }
+
+    fn run_seeded(seed: u64) -> Vec<u32> {
+        let mut app_builder = App::build();
+        app_builder
+            .set_rng_seed(seed)
+            .add_resource(Vec::<u32>::new())
+            .add_system(roll.system());
+        let app = &mut app_builder.app;
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let rolls = app.resources.get::<Vec<u32>>().unwrap();
+        (*rolls).clone()
+    }
+
+    #[test]
+    fn seeded_rng_is_reproducible() {
+        let rolls = run_seeded(42);
+        assert_eq!(rolls.len(), 3);
+        assert_eq!(rolls, run_seeded(42));
+        assert_ne!(rolls, run_seeded(7));
+    }
+}
@@ -4,13 +4,27 @@ use crate::{
     event::{EventReader, Events},
     plugin::Plugin,
 };
-use std::{thread, time::Duration};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
 
 /// Determines the method used to run an [App]'s `Schedule`
 #[derive(Copy, Clone, Debug)]
 pub enum RunMode {
-    Loop { wait: Option<Duration> },
+    Loop {
+        wait: Option<Duration>,
+    },
     Once,
+    /// Loops like [RunMode::Loop], but can be paused, resumed and stepped through the App's [RunControl] resource.
+    /// `wait` is also how often a paused App checks for new commands.
+    Controlled {
+        wait: Option<Duration>,
+    },
 }
 
 impl Default for RunMode {
@@ -19,6 +33,53 @@ impl Default for RunMode {
     }
 }
 
+/// A handle for pausing, resuming, and single-stepping an App that runs with [RunMode::Controlled]. Clones share
+/// the same state, so a clone can be kept (ex: by an editor) to control the App from another thread.
+#[derive(Clone, Default)]
+pub struct RunControl {
+    state: Arc<RunControlState>,
+}
+
+#[derive(Default)]
+struct RunControlState {
+    paused: AtomicBool,
+    pending_steps: AtomicUsize,
+}
+
+impl RunControl {
+    /// Stops running updates until [RunControl::resume] is called. Steps can still be run with [RunControl::step].
+    pub fn pause(&self) {
+        self.state.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.state.paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state.paused.load(Ordering::SeqCst)
+    }
+
+    /// Runs exactly one update while paused. Steps requested while running are ignored.
+    pub fn step(&self) {
+        self.state.pending_steps.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn should_update(&self) -> bool {
+        if !self.is_paused() {
+            self.state.pending_steps.store(0, Ordering::SeqCst);
+            return true;
+        }
+
+        self.state
+            .pending_steps
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |steps| {
+                steps.checked_sub(1)
+            })
+            .is_ok()
+    }
+}
+
 /// Configures an App to run its [Schedule](bevy_ecs::Schedule) according to a given [RunMode]
 #[derive(Default)]
 pub struct ScheduleRunnerPlugin {
@@ -39,11 +100,25 @@ impl ScheduleRunnerPlugin {
             },
         }
     }
+
+    pub fn run_controlled(wait_duration: Duration) -> Self {
+        ScheduleRunnerPlugin {
+            run_mode: RunMode::Controlled {
+                wait: Some(wait_duration),
+            },
+        }
+    }
 }
 
 impl Plugin for ScheduleRunnerPlugin {
     fn build(&self, app: &mut AppBuilder) {
         let run_mode = self.run_mode;
+        if let RunMode::Controlled { .. } = run_mode {
+            if app.resources().get::<RunControl>().is_none() {
+                app.add_resource(RunControl::default());
+            }
+        }
+
         app.set_runner(move |mut app: App| {
             let mut app_exit_event_reader = EventReader::<AppExit>::default();
             match run_mode {
@@ -69,7 +144,125 @@ impl Plugin for ScheduleRunnerPlugin {
                         thread::sleep(wait);
                     }
                 },
+                RunMode::Controlled { wait } => {
+                    let control = app
+                        .resources
+                        .get::<RunControl>()
+                        .map(|control| (*control).clone())
+                        .unwrap_or_default();
+                    loop {
+                        if let Some(app_exit_events) = app.resources.get_mut::<Events<AppExit>>() {
+                            if app_exit_event_reader.latest(&app_exit_events).is_some() {
+                                break;
+                            }
+                        }
+
+                        if control.should_update() {
+                            app.schedule.run(&mut app.world, &mut app.resources);
+                        }
+
+                        if let Some(wait) = wait {
+                            thread::sleep(wait);
+                        }
+                    }
+                }
             }
+
+            app.shutdown();
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{RunControl, ScheduleRunnerPlugin};
+    use crate::{app::AppExit, App, Events};
+    use bevy_ecs::{IntoQuerySystem, Res, ResMut};
+    use std::{
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
+
+    #[derive(Clone, Default)]
+    struct Updates {
+        count: Arc<AtomicUsize>,
+        exit: Arc<AtomicBool>,
+    }
+
+    fn update_system(updates: Res<Updates>, mut app_exit_events: ResMut<Events<AppExit>>) {
+        updates.count.fetch_add(1, Ordering::SeqCst);
+        if updates.exit.load(Ordering::SeqCst) {
+            app_exit_events.send(AppExit);
+        }
+    }
+
+    fn wait_for_updates(updates: &Updates, count: usize) {
+        while updates.count.load(Ordering::SeqCst) < count {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn controlled_run_mode() {
+        let control = RunControl::default();
+        control.pause();
+        let updates = Updates::default();
+
+        let controller = {
+            let control = control.clone();
+            let updates = updates.clone();
+            thread::spawn(move || {
+                control.step();
+                wait_for_updates(&updates, 1);
+                thread::sleep(Duration::from_millis(20));
+                assert_eq!(
+                    updates.count.load(Ordering::SeqCst),
+                    1,
+                    "a step runs exactly one update while paused"
+                );
+
+                updates.exit.store(true, Ordering::SeqCst);
+                control.step();
+            })
+        };
+
+        App::build()
+            .add_resource(control)
+            .add_resource(updates.clone())
+            .add_event::<AppExit>()
+            .add_plugin(ScheduleRunnerPlugin::run_controlled(Duration::from_millis(
+                1,
+            )))
+            .add_system(update_system.system())
+            .run();
+
+        controller.join().unwrap();
+        assert_eq!(updates.count.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn exit_handlers_run_on_app_exit() {
+        let updates = Updates::default();
+        updates.exit.store(true, Ordering::SeqCst);
+        let exits = Arc::new(AtomicUsize::new(0));
+
+        let handler_exits = exits.clone();
+        App::build()
+            .add_resource(updates.clone())
+            .add_event::<AppExit>()
+            .add_plugin(ScheduleRunnerPlugin::run_loop(Duration::from_millis(1)))
+            .add_system(update_system.system())
+            .add_exit_handler(move |resources| {
+                assert!(resources.get::<Updates>().is_some(), "resources are alive");
+                handler_exits.fetch_add(1, Ordering::SeqCst);
+            })
+            .run();
+
+        assert_eq!(updates.count.load(Ordering::SeqCst), 1);
+        assert_eq!(exits.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,16 +1,20 @@
 use crate::{
     filesystem_watcher::FilesystemWatcher, AssetLoadError, AssetLoadRequestHandler, AssetLoader,
-    Assets, Handle, HandleId, LoadRequest,
+    Assets, ByteTransform, Handle, HandleId, LoadGroup, LoadPriority, LoadRequest, RetryPolicy,
 };
 use anyhow::Result;
 use bevy_ecs::{Res, Resource, Resources};
-use crossbeam_channel::TryRecvError;
+use crossbeam_channel::{Receiver, Sender, TryRecvError};
 use std::{
     collections::{HashMap, HashSet},
     env, fs, io,
     path::{Path, PathBuf},
-    sync::{Arc, RwLock},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
     thread,
+    time::{Duration, Instant},
 };
 use thiserror::Error;
 
@@ -34,6 +38,14 @@ pub enum AssetServerError {
     Io(#[from] io::Error),
     #[error("Failed to watch asset folder.")]
     AssetWatchError { path: PathBuf },
+    #[error("No asset has been loaded from the given path.")]
+    AssetNotFound(PathBuf),
+    #[error("An asset has already been loaded from the given path.")]
+    AssetPathInUse(PathBuf),
+    #[error("Invalid data URI.")]
+    InvalidDataUri,
+    #[error("The asset server is shutting down.")]
+    ShuttingDown,
 }
 
 struct LoaderThread {
@@ -73,6 +85,9 @@ pub struct AssetServer {
     asset_folders: RwLock<Vec<PathBuf>>,
     loader_threads: RwLock<Vec<LoaderThread>>,
     max_loader_threads: usize,
+    retry_policy: RetryPolicy,
+    commit_budget: Option<usize>,
+    byte_transform: Option<ByteTransform>,
     asset_handlers: Arc<RwLock<Vec<Box<dyn AssetLoadRequestHandler>>>>,
     // TODO: this is a hack to enable retrieving generic AssetLoader<T>s. there must be a better way!
     loaders: Vec<Resources>,
@@ -80,6 +95,8 @@ pub struct AssetServer {
     extension_to_loader_index: HashMap<String, usize>,
     asset_info: RwLock<HashMap<HandleId, AssetInfo>>,
     asset_info_paths: RwLock<HashMap<PathBuf, HandleId>>,
+    load_listeners: RwLock<HashMap<HandleId, Vec<Sender<LoadState>>>>,
+    shutting_down: Arc<AtomicBool>,
     #[cfg(feature = "filesystem_watcher")]
     filesystem_watcher: Arc<RwLock<Option<FilesystemWatcher>>>,
 }
@@ -90,6 +107,9 @@ impl Default for AssetServer {
             #[cfg(feature = "filesystem_watcher")]
             filesystem_watcher: Arc::new(RwLock::new(None)),
             max_loader_threads: 4,
+            retry_policy: Default::default(),
+            commit_budget: None,
+            byte_transform: None,
             asset_folders: Default::default(),
             loader_threads: Default::default(),
             asset_handlers: Default::default(),
@@ -98,6 +118,8 @@ impl Default for AssetServer {
             extension_to_loader_index: Default::default(),
             asset_info_paths: Default::default(),
             asset_info: Default::default(),
+            load_listeners: Default::default(),
+            shutting_down: Default::default(),
         }
     }
 }
@@ -117,6 +139,46 @@ impl AssetServer {
         asset_handlers.push(Box::new(asset_handler));
     }
 
+    /// Sets the maximum number of threads used to load assets concurrently. Lowering the limit while loads are
+    /// in flight lets the excess threads finish their current queue, but no new requests are sent to them.
+    pub fn set_max_loader_threads(&mut self, max_loader_threads: usize) {
+        assert!(
+            max_loader_threads > 0,
+            "AssetServer needs at least one loader thread"
+        );
+        self.max_loader_threads = max_loader_threads;
+        self.loader_threads
+            .write()
+            .unwrap()
+            .truncate(max_loader_threads);
+    }
+
+    /// Sets how loads respond to transient io errors. By default, loads fail on the first error.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Limits how many loaded assets of each type are committed to their [Assets] collection per update. The rest
+    /// are committed on later updates, which spreads the cost of bulk loads across frames. By default there is no limit.
+    /// A budget of zero would never commit anything, so it panics.
+    pub fn set_commit_budget(&mut self, commit_budget: Option<usize>) {
+        assert_ne!(commit_budget, Some(0), "commit budget must be at least one");
+        self.commit_budget = commit_budget;
+    }
+
+    pub fn commit_budget(&self) -> Option<usize> {
+        self.commit_budget
+    }
+
+    /// Sets a transform applied to every asset's bytes right after they are read, before its loader sees them. This
+    /// can be used to load encrypted or otherwise preprocessed assets.
+    pub fn set_byte_transform(
+        &mut self,
+        transform: impl Fn(&Path, Vec<u8>) -> Result<Vec<u8>, AssetLoadError> + Send + Sync + 'static,
+    ) {
+        self.byte_transform = Some(ByteTransform::new(transform));
+    }
+
     pub fn add_loader<TLoader, TAsset>(&mut self, loader: TLoader)
     where
         TLoader: AssetLoader<TAsset>,
@@ -139,11 +201,95 @@ impl AssetServer {
     ) -> Result<Vec<HandleId>, AssetServerError> {
         let root_path = self.get_root_path()?;
         let asset_folder = root_path.join(path);
-        let handle_ids = self.load_assets_in_folder_recursive(&asset_folder)?;
+        let handle_ids =
+            self.load_assets_in_folder_recursive(&asset_folder, &|_| true, &mut |path| {
+                self.load_untyped(path)
+            })?;
         self.asset_folders.write().unwrap().push(asset_folder);
         Ok(handle_ids)
     }
 
+    /// Like [AssetServer::load_asset_folder], but at most `max_concurrent` of the folder's assets load at the same time.
+    /// The rest are queued and start loading as earlier ones finish, which smooths memory and IO pressure when loading
+    /// large folders.
+    pub fn load_folder_with_concurrency<P: AsRef<Path>>(
+        &self,
+        path: P,
+        max_concurrent: usize,
+    ) -> Result<Vec<HandleId>, AssetServerError> {
+        assert!(
+            max_concurrent > 0,
+            "folder loads need at least one concurrent load"
+        );
+        let root_path = self.get_root_path()?;
+        let asset_folder = root_path.join(path);
+        let mut load_requests = Vec::new();
+        let result = self.load_assets_in_folder_recursive(&asset_folder, &|_| true, &mut |path| {
+            let load_request = self.start_load(Path::new(path), LoadPriority::default())?;
+            let handle_id = load_request.handle_id;
+            load_requests.push(load_request);
+            Ok(handle_id)
+        });
+
+        // send the loads that were started even if walking the folder failed part way, so none stay loading forever
+        let load_group = Arc::new(LoadGroup::default());
+        let mut paths = Vec::new();
+        let mut initial_requests = Vec::new();
+        for (i, mut load_request) in load_requests.into_iter().enumerate() {
+            paths.push(load_request.path.clone());
+            load_request.load_group = Some(load_group.clone());
+            if i < max_concurrent {
+                initial_requests.push(load_request);
+            } else {
+                load_group.push(load_request);
+            }
+        }
+        // the group has to be filled before any of its loads can finish and pull from it
+        for load_request in initial_requests {
+            self.send_request_to_loader_thread(load_request);
+        }
+        for path in paths {
+            self.watch_asset_path(&path)?;
+        }
+
+        let handle_ids = result?;
+        self.asset_folders.write().unwrap().push(asset_folder);
+        Ok(handle_ids)
+    }
+
+    /// Loads every asset whose path (relative to the asset root) matches the given glob `pattern`, ex: `"textures/*.png"`.
+    /// `*` and `?` match any characters within a single path component, while `**` also matches across components.
+    pub fn load_asset_folder_glob(&self, pattern: &str) -> Result<Vec<HandleId>, AssetServerError> {
+        let root_path = self.get_root_path()?;
+        // only walk the part of the tree that could possibly match the pattern
+        let folder = pattern
+            .split('/')
+            .take_while(|component| !component.contains(['*', '?']))
+            .fold(PathBuf::new(), |folder, component| folder.join(component));
+        let folder = if pattern.split('/').count() == folder.components().count() {
+            folder
+                .parent()
+                .map(|parent| parent.to_owned())
+                .unwrap_or_default()
+        } else {
+            folder
+        };
+        let asset_folder = root_path.join(folder);
+        let pattern = pattern.chars().collect::<Vec<char>>();
+        self.load_assets_in_folder_recursive(
+            &asset_folder,
+            &|relative_path| {
+                let path = relative_path
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                glob_matches(&pattern, &path.chars().collect::<Vec<char>>())
+            },
+            &mut |path| self.load_untyped(path),
+        )
+    }
+
     pub fn get_handle<T, P: AsRef<Path>>(&self, path: P) -> Option<Handle<T>> {
         self.asset_info_paths
             .read()
@@ -248,6 +394,8 @@ impl AssetServer {
             .map(|handle_id| Handle::from(handle_id))
     }
 
+    /// Loads the asset at `path` on the current thread. `path` can also be a `data:<extension>[;base64],<payload>` URI,
+    /// whose payload is loaded by the loader for `<extension>` instead of being read from the filesystem.
     pub fn load_sync<T: Resource, P: AsRef<Path>>(
         &self,
         assets: &mut Assets<T>,
@@ -257,29 +405,77 @@ impl AssetServer {
         T: 'static,
     {
         let path = path.as_ref();
-        if let Some(ref extension) = path.extension() {
-            if let Some(index) = self.extension_to_loader_index.get(
-                extension
+        let (extension, data) = match path.to_str().and_then(|path| path.strip_prefix("data:")) {
+            Some(data_uri) => {
+                let (extension, data) = decode_data_uri(data_uri)?;
+                (extension, Some(data))
+            }
+            None => (
+                path.extension()
+                    .ok_or(AssetServerError::MissingAssetHandler)?
                     .to_str()
                     .expect("extension should be a valid string"),
-            ) {
-                let handle_id = HandleId::new();
-                let resources = &self.loaders[*index];
-                let loader = resources.get::<Box<dyn AssetLoader<T>>>().unwrap();
-                let asset = loader.load_from_file(path)?;
-                let handle = Handle::from(handle_id);
-                assets.set(handle, asset);
-                Ok(handle)
-            } else {
-                Err(AssetServerError::MissingAssetHandler)
+                None,
+            ),
+        };
+        let index = self
+            .extension_to_loader_index
+            .get(extension)
+            .ok_or(AssetServerError::MissingAssetHandler)?;
+
+        let handle_id = HandleId::new();
+        let resources = &self.loaders[*index];
+        let loader = resources.get::<Box<dyn AssetLoader<T>>>().unwrap();
+        let asset = ByteTransform::scope(self.byte_transform.as_ref(), || match data {
+            Some(data) => {
+                let bytes = ByteTransform::apply_current(path, data)?;
+                Ok(loader.from_bytes(path, bytes)?)
             }
-        } else {
-            Err(AssetServerError::MissingAssetHandler)
-        }
+            None => self.retry_policy.retry_load(|| loader.load_from_file(path)),
+        })?;
+        let handle = Handle::from(handle_id);
+        assets.set(handle, asset);
+        Ok(handle)
+    }
+
+    /// Like [AssetServer::load], but queued loads with a higher `priority` are handled first
+    pub fn load_with_priority<T, P: AsRef<Path>>(
+        &self,
+        path: P,
+        priority: LoadPriority,
+    ) -> Result<Handle<T>, AssetServerError> {
+        self.load_untyped_with_priority(path, priority)
+            .map(Handle::from)
     }
 
     pub fn load_untyped<P: AsRef<Path>>(&self, path: P) -> Result<HandleId, AssetServerError> {
+        self.load_untyped_with_priority(path, LoadPriority::default())
+    }
+
+    /// Like [AssetServer::load_untyped], but queued loads with a higher `priority` are handled first
+    pub fn load_untyped_with_priority<P: AsRef<Path>>(
+        &self,
+        path: P,
+        priority: LoadPriority,
+    ) -> Result<HandleId, AssetServerError> {
         let path = path.as_ref();
+        let load_request = self.start_load(path, priority)?;
+        let handle_id = load_request.handle_id;
+        self.send_request_to_loader_thread(load_request);
+        self.watch_asset_path(path)?;
+        Ok(handle_id)
+    }
+
+    /// Marks the asset at `path` as loading and returns the request that loads it
+    fn start_load(
+        &self,
+        path: &Path,
+        priority: LoadPriority,
+    ) -> Result<LoadRequest, AssetServerError> {
+        if self.shutting_down.load(Ordering::SeqCst) {
+            return Err(AssetServerError::ShuttingDown);
+        }
+
         if let Some(ref extension) = path.extension() {
             if let Some(index) = self.extension_to_handler_index.get(
                 extension
@@ -317,18 +513,16 @@ impl AssetServer {
                     }
                 };
 
-                self.send_request_to_loader_thread(LoadRequest {
+                Ok(LoadRequest {
                     handle_id,
                     path: path.to_owned(),
                     handler_index: *index,
                     version: new_version,
-                });
-
-                // TODO: watching each asset explicitly is a simpler implementation, its possible it would be more efficient to watch
-                // folders instead (when possible)
-                #[cfg(feature = "filesystem_watcher")]
-                Self::watch_path_for_changes(&mut self.filesystem_watcher.write().unwrap(), path)?;
-                Ok(handle_id)
+                    retry_policy: self.retry_policy,
+                    byte_transform: self.byte_transform.clone(),
+                    priority,
+                    load_group: None,
+                })
             } else {
                 Err(AssetServerError::MissingAssetHandler)
             }
@@ -337,16 +531,87 @@ impl AssetServer {
         }
     }
 
+    #[cfg_attr(not(feature = "filesystem_watcher"), allow(unused_variables))]
+    fn watch_asset_path(&self, path: &Path) -> Result<(), AssetServerError> {
+        // TODO: watching each asset explicitly is a simpler implementation, its possible it would be more efficient to watch
+        // folders instead (when possible)
+        #[cfg(feature = "filesystem_watcher")]
+        Self::watch_path_for_changes(&mut self.filesystem_watcher.write().unwrap(), path)?;
+        Ok(())
+    }
+
+    /// Moves the asset loaded from `from` to the path `to` and reloads it from there. Existing handles to the asset stay
+    /// valid and will point at the reloaded asset.
+    pub fn rename_asset<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to: Q,
+    ) -> Result<HandleId, AssetServerError> {
+        let (from, to) = (from.as_ref(), to.as_ref());
+        // check that the asset can be reloaded from `to` before touching any state
+        let has_handler = to
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| self.extension_to_handler_index.contains_key(extension));
+        if !has_handler {
+            return Err(AssetServerError::MissingAssetHandler);
+        }
+
+        {
+            let mut asset_info = self.asset_info.write().unwrap();
+            let mut asset_info_paths = self.asset_info_paths.write().unwrap();
+            if asset_info_paths.contains_key(to) {
+                return Err(AssetServerError::AssetPathInUse(to.to_owned()));
+            }
+
+            let handle_id = asset_info_paths
+                .remove(from)
+                .ok_or_else(|| AssetServerError::AssetNotFound(from.to_owned()))?;
+            asset_info_paths.insert(to.to_owned(), handle_id);
+            if let Some(asset_info) = asset_info.get_mut(&handle_id) {
+                asset_info.path = to.to_owned();
+            }
+        }
+
+        self.load_untyped(to)
+    }
+
     pub fn set_load_state(&self, handle_id: HandleId, load_state: LoadState) {
-        self.asset_info
-            .write()
-            .unwrap()
-            .get_mut(&handle_id)
-            .map(|asset_info| {
-                if load_state.get_version() >= asset_info.load_state.get_version() {
-                    asset_info.load_state = load_state;
+        let mut asset_info = self.asset_info.write().unwrap();
+        if let Some(asset_info) = asset_info.get_mut(&handle_id) {
+            if load_state.get_version() >= asset_info.load_state.get_version() {
+                asset_info.load_state = load_state.clone();
+                if let LoadState::Loaded(_) | LoadState::Failed(_) = load_state {
+                    let listeners = self.load_listeners.write().unwrap().remove(&handle_id);
+                    for listener in listeners.into_iter().flatten() {
+                        // the receiver may have been dropped, which is fine
+                        let _ = listener.send(load_state.clone());
+                    }
                 }
-            });
+            }
+        }
+    }
+
+    /// Returns a receiver that gets the asset's [LoadState] once, when it finishes loading or fails to load.
+    /// If the asset has already finished loading, the current state is sent immediately. Assets the server doesn't
+    /// know about never send a state.
+    pub fn on_loaded(&self, handle_id: HandleId) -> Receiver<LoadState> {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        // hold the asset_info lock so the state can't complete between this check and registering the listener
+        let asset_info = self.asset_info.read().unwrap();
+        match asset_info.get(&handle_id).map(|info| &info.load_state) {
+            Some(LoadState::Loading(_)) => self
+                .load_listeners
+                .write()
+                .unwrap()
+                .entry(handle_id)
+                .or_default()
+                .push(sender),
+            Some(load_state) => sender.send(load_state.clone()).unwrap(),
+            None => {}
+        }
+
+        receiver
     }
 
     pub fn get_load_state_untyped(&self, handle_id: HandleId) -> Option<LoadState> {
@@ -361,6 +626,34 @@ impl AssetServer {
         self.get_load_state_untyped(handle.id)
     }
 
+    /// Returns a snapshot of the path and current [LoadState] of every asset this server has started loading
+    pub fn iter_load_states(&self) -> Vec<(PathBuf, LoadState)> {
+        self.asset_info
+            .read()
+            .unwrap()
+            .values()
+            .map(|asset_info| (asset_info.path.clone(), asset_info.load_state.clone()))
+            .collect()
+    }
+
+    /// Returns handles to every asset this server has started loading whose extension maps to an [AssetLoader] for `T`
+    pub fn handles_of_type<T: 'static>(&self) -> Vec<Handle<T>> {
+        self.asset_info
+            .read()
+            .unwrap()
+            .values()
+            .filter(|asset_info| {
+                asset_info
+                    .path
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                    .and_then(|extension| self.extension_to_loader_index.get(extension))
+                    .is_some_and(|index| self.loaders[*index].contains::<Box<dyn AssetLoader<T>>>())
+            })
+            .map(|asset_info| Handle::from(asset_info.handle_id))
+            .collect()
+    }
+
     pub fn get_group_load_state(&self, handle_ids: &[HandleId]) -> Option<LoadState> {
         let mut load_state = LoadState::Loaded(0);
         for handle_id in handle_ids.iter() {
@@ -377,6 +670,31 @@ impl AssetServer {
         Some(load_state)
     }
 
+    /// Stops dispatching loads and waits up to `timeout` for loads that are already running to finish. Loads that are
+    /// still queued are dropped, and new loads fail with [AssetServerError::ShuttingDown]. Returns false if the timeout
+    /// elapsed before every loader thread stopped.
+    pub fn shutdown(&self, timeout: Duration) -> bool {
+        self.shutting_down.store(true, Ordering::SeqCst);
+        let deadline = Instant::now() + timeout;
+        loop {
+            // NOTE: a loader thread is running as long as it holds its own reference to its requests
+            let running = self
+                .loader_threads
+                .read()
+                .unwrap()
+                .iter()
+                .any(|loader_thread| Arc::strong_count(&loader_thread.requests) > 1);
+            if !running {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
     fn send_request_to_loader_thread(&self, load_request: LoadRequest) {
         // NOTE: This lock makes the call to Arc::strong_count safe. Removing (or reordering) it could result in undefined behavior
         let mut loader_threads = self.loader_threads.write().unwrap();
@@ -386,7 +704,11 @@ impl AssetServer {
             };
             let requests = loader_thread.requests.clone();
             loader_threads.push(loader_thread);
-            Self::start_thread(self.asset_handlers.clone(), requests);
+            Self::start_thread(
+                self.asset_handlers.clone(),
+                requests,
+                self.shutting_down.clone(),
+            );
         } else {
             let most_free_thread = loader_threads
                 .iter()
@@ -399,6 +721,7 @@ impl AssetServer {
                 Self::start_thread(
                     self.asset_handlers.clone(),
                     most_free_thread.requests.clone(),
+                    self.shutting_down.clone(),
                 );
             }
         }
@@ -407,22 +730,37 @@ impl AssetServer {
     fn start_thread(
         request_handlers: Arc<RwLock<Vec<Box<dyn AssetLoadRequestHandler>>>>,
         requests: Arc<RwLock<Vec<LoadRequest>>>,
+        shutting_down: Arc<AtomicBool>,
     ) {
         thread::spawn(move || {
             loop {
                 let request = {
                     let mut current_requests = requests.write().unwrap();
-                    if current_requests.len() == 0 {
-                        // if there are no requests, spin down the thread
+                    if current_requests.len() == 0 || shutting_down.load(Ordering::SeqCst) {
+                        // if there are no requests, spin down the thread. on shutdown, queued requests are dropped
+                        current_requests.clear();
                         break;
                     }
 
-                    current_requests.pop().unwrap()
+                    // max_by_key picks the last of equally prioritized requests, so ties keep the old pop order
+                    let (index, _) = current_requests
+                        .iter()
+                        .enumerate()
+                        .max_by_key(|(_, request)| request.priority)
+                        .unwrap();
+                    current_requests.remove(index)
                 };
 
                 let handlers = request_handlers.read().unwrap();
                 let request_handler = &handlers[request.handler_index];
                 request_handler.handle_request(&request);
+
+                // hand this load's slot in its group to the group's next queued load
+                if let Some(next_request) =
+                    request.load_group.as_ref().and_then(|group| group.pop())
+                {
+                    requests.write().unwrap().push(next_request);
+                }
             }
         });
     }
@@ -430,6 +768,8 @@ impl AssetServer {
     fn load_assets_in_folder_recursive(
         &self,
         path: &Path,
+        filter: &dyn Fn(&Path) -> bool,
+        load: &mut dyn FnMut(&str) -> Result<HandleId, AssetServerError>,
     ) -> Result<Vec<HandleId>, AssetServerError> {
         if !path.is_dir() {
             return Err(AssetServerError::AssetFolderNotADirectory(
@@ -443,10 +783,18 @@ impl AssetServer {
             let entry = entry?;
             let child_path = entry.path();
             if child_path.is_dir() {
-                handle_ids.extend(self.load_assets_in_folder_recursive(&child_path)?);
+                handle_ids.extend(self.load_assets_in_folder_recursive(
+                    &child_path,
+                    filter,
+                    load,
+                )?);
             } else {
                 let relative_child_path = child_path.strip_prefix(&root_path).unwrap();
-                let handle = match self.load_untyped(
+                if !filter(relative_child_path) {
+                    continue;
+                }
+
+                let handle = match load(
                     relative_child_path
                         .to_str()
                         .expect("Path should be a valid string"),
@@ -463,3 +811,661 @@ impl AssetServer {
         Ok(handle_ids)
     }
 }
+
+/// Matches a `/`-separated `path` against a glob `pattern` supporting `*`, `?`, and `**`
+fn glob_matches(pattern: &[char], path: &[char]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some(('*', rest)) if rest.first() == Some(&'*') => {
+            let rest = &rest[1..];
+            // "**/" can also match zero directories
+            if rest.first() == Some(&'/') && glob_matches(&rest[1..], path) {
+                return true;
+            }
+            (0..=path.len()).any(|i| glob_matches(rest, &path[i..]))
+        }
+        Some(('*', rest)) => {
+            for i in 0..=path.len() {
+                if glob_matches(rest, &path[i..]) {
+                    return true;
+                }
+                if i < path.len() && path[i] == '/' {
+                    break;
+                }
+            }
+            false
+        }
+        Some(('?', rest)) => match path.split_first() {
+            Some((c, path_rest)) => *c != '/' && glob_matches(rest, path_rest),
+            None => false,
+        },
+        Some((c, rest)) => match path.split_first() {
+            Some((path_c, path_rest)) => c == path_c && glob_matches(rest, path_rest),
+            None => false,
+        },
+    }
+}
+
+/// Splits the part of a `data:<extension>[;base64],<payload>` URI after `data:` into the extension used to pick a
+/// loader and the decoded payload
+fn decode_data_uri(data_uri: &str) -> Result<(&str, Vec<u8>), AssetServerError> {
+    let comma = data_uri.find(',').ok_or(AssetServerError::InvalidDataUri)?;
+    let (media_type, payload) = (&data_uri[..comma], &data_uri[comma + 1..]);
+    match media_type.strip_suffix(";base64") {
+        Some(extension) => base64::decode(payload)
+            .map(|data| (extension, data))
+            .map_err(|_| AssetServerError::InvalidDataUri),
+        None => Ok((media_type, payload.as_bytes().to_vec())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glob_matches, AssetServer, AssetServerError, HandleId};
+    use crate::{
+        AssetLoadError, AssetLoadRequestHandler, AssetLoader, Assets, LoadPriority, LoadRequest,
+        LoadState, RetryPolicy,
+    };
+    use bevy_app::{App, AppExit, Events, ScheduleRunnerPlugin};
+    use bevy_ecs::{IntoQuerySystem, Res, ResMut};
+    use crossbeam_channel::{Receiver, Sender};
+    use std::{
+        collections::HashSet,
+        fs, io,
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        thread,
+        time::Duration,
+    };
+
+    #[derive(Default)]
+    struct ConcurrencyTracker {
+        in_flight: AtomicUsize,
+        peak: AtomicUsize,
+        finished: AtomicUsize,
+    }
+
+    struct SlowHandler(Arc<ConcurrencyTracker>);
+
+    impl AssetLoadRequestHandler for SlowHandler {
+        fn handle_request(&self, _load_request: &LoadRequest) {
+            let in_flight = self.0.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.0.peak.fetch_max(in_flight, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(5));
+            self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+            self.0.finished.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["slow"]
+        }
+    }
+
+    struct NoopHandler;
+
+    impl AssetLoadRequestHandler for NoopHandler {
+        fn handle_request(&self, _load_request: &LoadRequest) {}
+
+        fn extensions(&self) -> &[&str] {
+            &["noop"]
+        }
+    }
+
+    #[test]
+    fn load_state_snapshot() {
+        let mut asset_server = AssetServer::default();
+        asset_server.add_handler(NoopHandler);
+
+        let loaded = asset_server.load_untyped("loaded.noop").unwrap();
+        let failed = asset_server.load_untyped("failed.noop").unwrap();
+        asset_server.load_untyped("loading.noop").unwrap();
+        asset_server.set_load_state(loaded, LoadState::Loaded(0));
+        asset_server.set_load_state(failed, LoadState::Failed(0));
+
+        let mut load_states = asset_server.iter_load_states();
+        load_states.sort_by(|(a, _), (b, _)| a.cmp(b));
+        assert_eq!(
+            load_states,
+            vec![
+                (PathBuf::from("failed.noop"), LoadState::Failed(0)),
+                (PathBuf::from("loaded.noop"), LoadState::Loaded(0)),
+                (PathBuf::from("loading.noop"), LoadState::Loading(0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn rename_asset() {
+        let mut asset_server = AssetServer::default();
+        asset_server.add_handler(NoopHandler);
+
+        let handle_id = asset_server.load_untyped("old.noop").unwrap();
+        asset_server.set_load_state(handle_id, LoadState::Loaded(0));
+        asset_server.load_untyped("taken.noop").unwrap();
+
+        assert_eq!(
+            asset_server.rename_asset("old.noop", "new.noop").unwrap(),
+            handle_id,
+            "renamed assets keep their handle"
+        );
+        assert_eq!(
+            asset_server
+                .get_handle::<(), _>("new.noop")
+                .map(|handle| handle.id),
+            Some(handle_id)
+        );
+        assert!(asset_server.get_handle::<(), _>("old.noop").is_none());
+        assert!(
+            matches!(
+                asset_server.get_load_state_untyped(handle_id),
+                Some(LoadState::Loading(_))
+            ),
+            "the asset is reloaded from its new path"
+        );
+
+        assert!(matches!(
+            asset_server.rename_asset("old.noop", "other.noop"),
+            Err(AssetServerError::AssetNotFound(_))
+        ));
+        assert!(matches!(
+            asset_server.rename_asset("new.noop", "taken.noop"),
+            Err(AssetServerError::AssetPathInUse(_))
+        ));
+
+        assert!(matches!(
+            asset_server.rename_asset("new.noop", "new.unsupported"),
+            Err(AssetServerError::MissingAssetHandler)
+        ));
+        assert_eq!(
+            asset_server
+                .get_handle::<(), _>("new.noop")
+                .map(|handle| handle.id),
+            Some(handle_id),
+            "failed renames leave the asset at its old path"
+        );
+        assert!(asset_server
+            .get_handle::<(), _>("new.unsupported")
+            .is_none());
+        let mut paths = asset_server
+            .iter_load_states()
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect::<Vec<PathBuf>>();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("new.noop"), PathBuf::from("taken.noop")]
+        );
+    }
+
+    #[test]
+    fn on_loaded_fires_once() {
+        let mut asset_server = AssetServer::default();
+        asset_server.add_handler(NoopHandler);
+
+        let handle_id = asset_server.load_untyped("asset.noop").unwrap();
+        let receiver = asset_server.on_loaded(handle_id);
+        assert!(receiver.try_recv().is_err(), "asset is still loading");
+
+        asset_server.set_load_state(handle_id, LoadState::Loaded(0));
+        asset_server.set_load_state(handle_id, LoadState::Loaded(0));
+        assert_eq!(receiver.try_recv(), Ok(LoadState::Loaded(0)));
+        assert!(receiver.try_recv().is_err(), "completion is only sent once");
+
+        assert_eq!(
+            asset_server.on_loaded(handle_id).try_recv(),
+            Ok(LoadState::Loaded(0)),
+            "listening to a loaded asset fires immediately"
+        );
+    }
+
+    #[test]
+    fn loads_respect_max_loader_threads() {
+        let tracker = Arc::new(ConcurrencyTracker::default());
+        let mut asset_server = AssetServer::default();
+        asset_server.add_handler(SlowHandler(tracker.clone()));
+        asset_server.set_max_loader_threads(2);
+
+        for i in 0..20 {
+            asset_server
+                .load_untyped(format!("{}.slow", i))
+                .expect("handler exists for extension");
+        }
+
+        while tracker.finished.load(Ordering::SeqCst) < 20 {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        assert!(tracker.peak.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[test]
+    fn shutdown_stops_dispatching_loads() {
+        let tracker = Arc::new(ConcurrencyTracker::default());
+        let mut asset_server = AssetServer::default();
+        asset_server.add_handler(SlowHandler(tracker.clone()));
+        asset_server.set_max_loader_threads(2);
+
+        for i in 0..20 {
+            asset_server
+                .load_untyped(format!("{}.slow", i))
+                .expect("handler exists for extension");
+        }
+
+        assert!(asset_server.shutdown(Duration::from_secs(5)));
+        assert_eq!(tracker.in_flight.load(Ordering::SeqCst), 0);
+        let finished = tracker.finished.load(Ordering::SeqCst);
+        assert!(finished < 20, "queued loads are dropped");
+        assert!(matches!(
+            asset_server.load_untyped("late.slow"),
+            Err(AssetServerError::ShuttingDown)
+        ));
+
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(tracker.finished.load(Ordering::SeqCst), finished);
+    }
+
+    #[test]
+    fn app_exit_mid_load_shuts_down_asset_server() {
+        fn load_system(
+            asset_server: Res<AssetServer>,
+            mut app_exit_events: ResMut<Events<AppExit>>,
+        ) {
+            for i in 0..20 {
+                asset_server
+                    .load_untyped(format!("{}.slow", i))
+                    .expect("handler exists for extension");
+            }
+            app_exit_events.send(AppExit);
+        }
+
+        let tracker = Arc::new(ConcurrencyTracker::default());
+        let mut asset_server = AssetServer::default();
+        asset_server.add_handler(SlowHandler(tracker.clone()));
+
+        App::build()
+            .add_resource(asset_server)
+            .add_plugin(ScheduleRunnerPlugin::run_loop(Duration::from_millis(1)))
+            .add_system(load_system.system())
+            .add_exit_handler(crate::shutdown_asset_server)
+            .run();
+
+        // the app and its asset server are dropped, but no loader thread is left running
+        assert_eq!(tracker.in_flight.load(Ordering::SeqCst), 0);
+        assert!(tracker.finished.load(Ordering::SeqCst) < 20);
+    }
+
+    #[test]
+    fn folder_loads_respect_max_concurrent() {
+        let root =
+            std::env::temp_dir().join(format!("bevy_asset_concurrency_{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        for i in 0..12 {
+            fs::write(root.join(format!("{}.slow", i)), b"").unwrap();
+        }
+        let folder = asset_root_relative(&root);
+
+        let tracker = Arc::new(ConcurrencyTracker::default());
+        let mut asset_server = AssetServer::default();
+        asset_server.add_handler(SlowHandler(tracker.clone()));
+        let handle_ids = asset_server.load_folder_with_concurrency(&folder, 2);
+        fs::remove_dir_all(&root).unwrap();
+        let handle_ids = handle_ids.unwrap();
+
+        while tracker.finished.load(Ordering::SeqCst) < 12 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(tracker.peak.load(Ordering::SeqCst) <= 2);
+
+        // like load_asset_folder, every file's handle is returned
+        let mut expected = (0..12)
+            .map(|i| {
+                asset_server
+                    .get_handle::<(), _>(format!("{}/{}.slow", folder, i))
+                    .unwrap()
+                    .id
+            })
+            .collect::<HashSet<HandleId>>();
+        for handle_id in handle_ids {
+            assert!(expected.remove(&handle_id));
+        }
+        assert!(expected.is_empty());
+    }
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        glob_matches(
+            &pattern.chars().collect::<Vec<char>>(),
+            &path.chars().collect::<Vec<char>>(),
+        )
+    }
+
+    #[test]
+    fn glob_patterns() {
+        let files = [
+            "assets/textures/a.png",
+            "assets/textures/b.jpg",
+            "assets/textures/ui/c.png",
+            "assets/models/d.gltf",
+        ];
+
+        let matching = |pattern: &str| {
+            files
+                .iter()
+                .filter(|path| matches(pattern, path))
+                .cloned()
+                .collect::<Vec<&str>>()
+        };
+
+        assert_eq!(
+            matching("assets/textures/*.png"),
+            vec!["assets/textures/a.png"]
+        );
+        assert_eq!(
+            matching("assets/**/*.png"),
+            vec!["assets/textures/a.png", "assets/textures/ui/c.png"]
+        );
+        assert_eq!(
+            matching("assets/textures/?.jpg"),
+            vec!["assets/textures/b.jpg"]
+        );
+        assert_eq!(matching("assets/**"), files.to_vec());
+        assert!(matching("assets/*.png").is_empty());
+    }
+
+    /// Returns a `/`-separated path to `path` relative to the asset root. Folder loads only take paths relative to the
+    /// root, so tests use this to load fixtures from the temp directory.
+    fn asset_root_relative(path: &Path) -> String {
+        // ".." is resolved against the real directories, so compare canonical paths
+        let asset_root = fs::canonicalize(env!("CARGO_MANIFEST_DIR")).unwrap();
+        let path = fs::canonicalize(path).unwrap();
+        let shared = asset_root
+            .components()
+            .zip(path.components())
+            .take_while(|(a, b)| a == b)
+            .count();
+        asset_root
+            .components()
+            .skip(shared)
+            .map(|_| "..".to_string())
+            .chain(
+                path.components()
+                    .skip(shared)
+                    .map(|component| component.as_os_str().to_string_lossy().into_owned()),
+            )
+            .collect::<Vec<String>>()
+            .join("/")
+    }
+
+    #[test]
+    fn load_asset_folder_glob() {
+        let root = std::env::temp_dir().join(format!("bevy_asset_glob_{}", std::process::id()));
+        fs::create_dir_all(root.join("sub")).unwrap();
+        for file in &["a.noop", "b.txt", "sub/c.noop", "sub/d.png"] {
+            fs::write(root.join(file), b"").unwrap();
+        }
+
+        let folder = asset_root_relative(&root);
+
+        let loaded = |pattern: &str| {
+            let mut asset_server = AssetServer::default();
+            asset_server.add_handler(NoopHandler);
+            let result = asset_server.load_asset_folder_glob(&format!("{}/{}", folder, pattern));
+            let mut paths = asset_server
+                .iter_load_states()
+                .into_iter()
+                .map(|(path, _)| path.strip_prefix(&folder).unwrap().to_owned())
+                .collect::<Vec<PathBuf>>();
+            paths.sort();
+            result.map(|handle_ids| (handle_ids.len(), paths))
+        };
+
+        let results = (
+            loaded("**/*.noop"),
+            loaded("sub/*"),
+            loaded("a.noop"),
+            loaded("missing/*.noop"),
+        );
+        fs::remove_dir_all(&root).unwrap();
+
+        // files without a handler are skipped
+        assert_eq!(
+            results.0.unwrap(),
+            (
+                2,
+                vec![PathBuf::from("a.noop"), PathBuf::from("sub/c.noop")]
+            )
+        );
+        assert_eq!(results.1.unwrap(), (1, vec![PathBuf::from("sub/c.noop")]));
+        // a pattern without wildcards walks its parent folder
+        assert_eq!(results.2.unwrap(), (1, vec![PathBuf::from("a.noop")]));
+        assert!(matches!(
+            results.3,
+            Err(AssetServerError::AssetFolderNotADirectory(_))
+        ));
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Mesh {
+        indices: Vec<u8>,
+    }
+
+    struct MeshLoader;
+
+    impl AssetLoader<Mesh> for MeshLoader {
+        fn from_bytes(&self, _asset_path: &Path, bytes: Vec<u8>) -> anyhow::Result<Mesh> {
+            Ok(Mesh { indices: bytes })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["mesh"]
+        }
+    }
+
+    #[test]
+    fn load_data_uri() {
+        let mut asset_server = AssetServer::default();
+        asset_server.add_loader(MeshLoader);
+        let mut assets = Assets::<Mesh>::default();
+
+        // "AAECAw==" is base64 for [0, 1, 2, 3]
+        let handle = asset_server
+            .load_sync(&mut assets, "data:mesh;base64,AAECAw==")
+            .unwrap();
+        assert_eq!(
+            assets.get(&handle),
+            Some(&Mesh {
+                indices: vec![0, 1, 2, 3]
+            })
+        );
+
+        let handle = asset_server
+            .load_sync(&mut assets, "data:mesh,abc")
+            .unwrap();
+        assert_eq!(assets.get(&handle).unwrap().indices, b"abc");
+
+        match asset_server.load_sync(&mut assets, "data:mesh;base64,???") {
+            Err(AssetServerError::InvalidDataUri) => {}
+            _ => panic!("invalid base64 payloads are rejected"),
+        }
+    }
+
+    struct TextLoader;
+
+    impl AssetLoader<String> for TextLoader {
+        fn from_bytes(&self, _asset_path: &Path, bytes: Vec<u8>) -> anyhow::Result<String> {
+            Ok(String::from_utf8(bytes)?)
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+    }
+
+    #[test]
+    fn byte_transform_runs_before_loader() {
+        const KEY: u8 = 0x5a;
+        let path = std::env::temp_dir().join("bevy_asset_byte_transform.txt");
+        let encrypted = b"secret".iter().map(|byte| byte ^ KEY).collect::<Vec<u8>>();
+        fs::write(&path, encrypted).unwrap();
+
+        let mut asset_server = AssetServer::default();
+        asset_server.add_loader(TextLoader);
+        asset_server.set_byte_transform(|_path, bytes| {
+            Ok(bytes.into_iter().map(|byte| byte ^ KEY).collect())
+        });
+
+        let mut assets = Assets::<String>::default();
+        let handle = asset_server.load_sync(&mut assets, &path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            assets.get(&handle).map(|text| text.as_str()),
+            Some("secret")
+        );
+    }
+
+    struct FlakyLoader(AtomicUsize);
+
+    impl AssetLoader<usize> for FlakyLoader {
+        fn from_bytes(&self, _asset_path: &Path, _bytes: Vec<u8>) -> anyhow::Result<usize> {
+            unreachable!("FlakyLoader overrides load_from_file")
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["flaky"]
+        }
+
+        fn load_from_file(&self, _asset_path: &Path) -> Result<usize, AssetLoadError> {
+            let attempts = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            if attempts <= 2 {
+                Err(io::Error::new(io::ErrorKind::Interrupted, "transient").into())
+            } else {
+                Ok(attempts)
+            }
+        }
+    }
+
+    #[test]
+    fn load_sync_retries_load_from_file() {
+        let mut asset_server = AssetServer::default();
+        asset_server.add_loader(FlakyLoader(AtomicUsize::new(0)));
+        asset_server.set_retry_policy(RetryPolicy {
+            max_retries: 2,
+            backoff: Duration::from_millis(1),
+        });
+
+        let mut assets = Assets::<usize>::default();
+        let handle = asset_server.load_sync(&mut assets, "a.flaky").unwrap();
+        assert_eq!(
+            assets.get(&handle),
+            Some(&3),
+            "the asset loads on the third attempt"
+        );
+    }
+
+    struct BytesLoader;
+
+    impl AssetLoader<Vec<u8>> for BytesLoader {
+        fn from_bytes(&self, _asset_path: &Path, bytes: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+            Ok(bytes)
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["bin"]
+        }
+    }
+
+    struct ExtensionsHandler(&'static [&'static str]);
+
+    impl AssetLoadRequestHandler for ExtensionsHandler {
+        fn handle_request(&self, _load_request: &LoadRequest) {}
+
+        fn extensions(&self) -> &[&str] {
+            self.0
+        }
+    }
+
+    #[test]
+    fn handles_of_type() {
+        let mut asset_server = AssetServer::default();
+        asset_server.add_loader(TextLoader);
+        asset_server.add_loader(BytesLoader);
+        asset_server.add_handler(ExtensionsHandler(&["txt", "bin"]));
+
+        let a = asset_server.load::<String, _>("a.txt").unwrap();
+        let b = asset_server.load::<Vec<u8>, _>("b.bin").unwrap();
+        let c = asset_server.load::<String, _>("c.txt").unwrap();
+
+        let text_handles = asset_server.handles_of_type::<String>();
+        assert_eq!(text_handles.len(), 2);
+        assert!(text_handles.contains(&a) && text_handles.contains(&c));
+        assert_eq!(asset_server.handles_of_type::<Vec<u8>>(), vec![b]);
+        assert!(asset_server.handles_of_type::<u32>().is_empty());
+    }
+
+    struct OrderHandler {
+        blocker: Mutex<Receiver<()>>,
+        order: Sender<PathBuf>,
+    }
+
+    impl AssetLoadRequestHandler for OrderHandler {
+        fn handle_request(&self, load_request: &LoadRequest) {
+            self.order.send(load_request.path.clone()).unwrap();
+            if load_request.path == Path::new("blocker.order") {
+                self.blocker.lock().unwrap().recv().unwrap();
+            }
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["order"]
+        }
+    }
+
+    #[test]
+    fn load_priority() {
+        let (unblock, blocker) = crossbeam_channel::unbounded();
+        let (order, handled) = crossbeam_channel::unbounded();
+        let mut asset_server = AssetServer::default();
+        asset_server.set_max_loader_threads(1);
+        asset_server.add_handler(OrderHandler {
+            blocker: Mutex::new(blocker),
+            order,
+        });
+
+        // occupy the only loader thread so the following loads are queued together
+        asset_server.load_untyped("blocker.order").unwrap();
+        let mut handled_paths = vec![handled.recv_timeout(Duration::from_secs(5)).unwrap()];
+        for path in &["low_0.order", "low_1.order", "low_2.order"] {
+            asset_server
+                .load_untyped_with_priority(path, LoadPriority::Low)
+                .unwrap();
+        }
+        asset_server.load_untyped("normal.order").unwrap();
+        asset_server
+            .load_with_priority::<(), _>("high.order", LoadPriority::High)
+            .unwrap();
+        unblock.send(()).unwrap();
+
+        handled_paths.extend((0..5).map(|_| handled.recv_timeout(Duration::from_secs(5)).unwrap()));
+        assert_eq!(
+            handled_paths[..3],
+            [
+                PathBuf::from("blocker.order"),
+                PathBuf::from("high.order"),
+                PathBuf::from("normal.order"),
+            ],
+            "the high priority load is handled first, even though it was queued last"
+        );
+        handled_paths[3..].sort();
+        assert_eq!(
+            handled_paths[3..],
+            [
+                PathBuf::from("low_0.order"),
+                PathBuf::from("low_1.order"),
+                PathBuf::from("low_2.order"),
+            ]
+        );
+    }
+}
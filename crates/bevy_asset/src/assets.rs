@@ -77,6 +77,21 @@ impl<T: Resource> Assets<T> {
         self.assets.get_mut(&handle)
     }
 
+    /// Mutates the asset with the given id in place and sends an [AssetEvent::Modified] event. Unlike [Assets::set],
+    /// this keeps the rest of the asset intact, so consumers can react to partial changes (ex: a single mesh attribute).
+    /// Returns `false` if the asset does not exist.
+    pub fn modify<I: Into<HandleId>>(&mut self, id: I, f: impl FnOnce(&mut T)) -> bool {
+        let handle = Handle::from_id(id.into());
+        match self.assets.get_mut(&handle) {
+            Some(asset) => {
+                f(asset);
+                self.events.send(AssetEvent::Modified { handle });
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn get_or_insert_with(
         &mut self,
         handle: Handle<T>,
@@ -102,6 +117,20 @@ impl<T: Resource> Assets<T> {
         self.assets.remove(&handle)
     }
 
+    /// Removes the asset with the given id and returns it by value, sending an [AssetEvent::Removed] event.
+    /// Handles pointing at the asset will resolve to `None` afterward.
+    pub fn take<I: Into<HandleId>>(&mut self, id: I) -> Option<T> {
+        let handle = Handle::from_id(id.into());
+        let asset = self.assets.remove(&handle)?;
+        self.events.send(AssetEvent::Removed { handle });
+        Some(asset)
+    }
+
+    /// Reserves capacity for at least `additional` more assets, avoiding reallocations when many assets are added at once
+    pub fn reserve(&mut self, additional: usize) {
+        self.assets.reserve(additional);
+    }
+
     pub fn asset_event_system(
         mut events: ResMut<Events<AssetEvent<T>>>,
         mut assets: ResMut<Assets<T>>,
@@ -166,3 +195,84 @@ impl AddAsset for AppBuilder {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{AssetEvent, Assets};
+
+    #[derive(Debug, PartialEq)]
+    struct Mesh {
+        positions: Vec<[f32; 3]>,
+        normals: Vec<[f32; 3]>,
+    }
+
+    #[test]
+    fn modify_asset() {
+        let mut assets = Assets::<Mesh>::default();
+        let handle = assets.add(Mesh {
+            positions: vec![[0.0, 1.0, 0.0]],
+            normals: vec![[0.0, 0.0, 0.0]],
+        });
+        assets.events.drain().for_each(drop);
+
+        assert!(assets.modify(handle, |mesh| mesh.normals = vec![[0.0, 0.0, 1.0]]));
+        assert_eq!(
+            assets.get(&handle),
+            Some(&Mesh {
+                positions: vec![[0.0, 1.0, 0.0]],
+                normals: vec![[0.0, 0.0, 1.0]],
+            }),
+            "only the modified attribute changes"
+        );
+
+        let events = assets.events.drain().collect::<Vec<_>>();
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            AssetEvent::Modified { handle: modified } => assert_eq!(*modified, handle),
+            _ => panic!("modifying an asset sends a Modified event"),
+        }
+
+        assets.take(handle);
+        assert!(!assets.modify(handle, |_| panic!("missing assets are not modified")));
+    }
+
+    #[test]
+    fn reserve_assets() {
+        let mut assets = Assets::<usize>::default();
+        assets.reserve(100);
+        let capacity = assets.assets.capacity();
+        assert!(capacity >= 100);
+
+        for i in 0..100 {
+            assets.add(i);
+        }
+        assert_eq!(
+            assets.assets.capacity(),
+            capacity,
+            "adding up to the reserved count does not reallocate"
+        );
+    }
+
+    #[test]
+    fn take_asset() {
+        let mut assets = Assets::<usize>::default();
+        let handle = assets.add(42);
+
+        assert_eq!(assets.take(handle), Some(42));
+        assert_eq!(assets.take(handle), None, "an asset can only be taken once");
+        assert!(assets.get(&handle).is_none());
+
+        let removed = assets
+            .events
+            .drain()
+            .filter(|event| match event {
+                AssetEvent::Removed { handle: removed } => *removed == handle,
+                _ => false,
+            })
+            .count();
+        assert_eq!(
+            removed, 1,
+            "taking an asset sends exactly one Removed event"
+        );
+    }
+}
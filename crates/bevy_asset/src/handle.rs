@@ -25,6 +25,8 @@ impl HandleId {
 /// A handle into a specific Asset of type `T`
 ///
 /// Handles contain a unique id that corresponds to a specific asset in the [Assets](crate::Assets) collection.
+/// Equality and hashing only consider that id, so any two handles to the same asset are interchangeable as `HashMap` keys,
+/// regardless of how they were created.
 #[derive(Properties)]
 pub struct Handle<T>
 where
@@ -43,6 +45,10 @@ impl<T> Handle<T> {
         }
     }
 
+    pub fn id(&self) -> HandleId {
+        self.id
+    }
+
     /// Gets a handle for the given type that has this handle's id. This is useful when an
     /// asset is derived from another asset. In this case, a common handle can be used to
     /// correlate them.
@@ -90,6 +96,12 @@ impl<T> From<HandleId> for Handle<T> {
     }
 }
 
+impl<T> From<Handle<T>> for HandleId {
+    fn from(handle: Handle<T>) -> Self {
+        handle.id
+    }
+}
+
 impl<T> From<u128> for Handle<T> {
     fn from(value: u128) -> Self {
         Handle::from_u128(value)
@@ -188,3 +200,23 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Handle, HandleUntyped};
+    use std::collections::HashMap;
+
+    #[test]
+    fn handles_with_same_id_are_equal_keys() {
+        let handle = Handle::<usize>::new();
+        let mut map = HashMap::new();
+        map.insert(handle, "asset");
+
+        let from_id = Handle::<usize>::from_id(handle.id());
+        let from_untyped = Handle::<usize>::from(HandleUntyped::from(handle));
+        assert_eq!(from_id, handle);
+        assert_eq!(map.get(&from_id), Some(&"asset"));
+        assert_eq!(map.get(&from_untyped), Some(&"asset"));
+        assert_eq!(map.get(&Handle::<usize>::new()), None);
+    }
+}
@@ -5,12 +5,14 @@ mod filesystem_watcher;
 mod handle;
 mod load_request;
 mod loader;
+mod startup_assets;
 
 pub use asset_server::*;
 pub use assets::*;
 pub use handle::*;
 pub use load_request::*;
 pub use loader::*;
+pub use startup_assets::*;
 
 /// The names of asset stages in an App Schedule
 pub mod stage {
@@ -19,12 +21,16 @@ pub mod stage {
 }
 
 pub mod prelude {
-    pub use crate::{AddAsset, AssetEvent, AssetServer, Assets, Handle};
+    pub use crate::{AddAsset, AssetEvent, AssetServer, Assets, Handle, LoadOnStartup};
 }
 
 use bevy_app::{prelude::Plugin, AppBuilder};
-use bevy_ecs::IntoQuerySystem;
+use bevy_ecs::{IntoQuerySystem, Resources};
 use bevy_type_registry::RegisterType;
+use std::time::Duration;
+
+/// How long an exiting App waits for in-flight asset loads to finish
+pub const ASSET_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Adds support for Assets to an App. Assets are typed collections with change tracking, which are added as App Resources.
 /// Examples of assets: textures, sounds, 3d models, maps, scenes
@@ -36,7 +42,8 @@ impl Plugin for AssetPlugin {
         app.add_stage_before(bevy_app::stage::PRE_UPDATE, stage::LOAD_ASSETS)
             .add_stage_after(bevy_app::stage::POST_UPDATE, stage::ASSET_EVENTS)
             .init_resource::<AssetServer>()
-            .register_property::<HandleId>();
+            .register_property::<HandleId>()
+            .add_exit_handler(shutdown_asset_server);
 
         #[cfg(feature = "filesystem_watcher")]
         app.add_system_to_stage(
@@ -45,3 +52,14 @@ impl Plugin for AssetPlugin {
         );
     }
 }
+
+pub(crate) fn shutdown_asset_server(resources: &Resources) {
+    if let Some(asset_server) = resources.get::<AssetServer>() {
+        if !asset_server.shutdown(ASSET_SHUTDOWN_TIMEOUT) {
+            log::warn!(
+                "Asset loads were still running after {:?}",
+                ASSET_SHUTDOWN_TIMEOUT
+            );
+        }
+    }
+}
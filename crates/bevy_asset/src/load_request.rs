@@ -1,9 +1,15 @@
 use crate::{AssetLoadError, AssetLoader, AssetResult, AssetVersion, Handle, HandleId};
 use anyhow::Result;
 use crossbeam_channel::Sender;
-use fs::File;
-use io::Read;
-use std::{fs, io, path::PathBuf};
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fmt, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
 
 /// A request from an [AssetServer](crate::AssetServer) to load an asset.
 #[derive(Debug)]
@@ -12,6 +18,156 @@ pub struct LoadRequest {
     pub handle_id: HandleId,
     pub handler_index: usize,
     pub version: AssetVersion,
+    pub retry_policy: RetryPolicy,
+    pub byte_transform: Option<ByteTransform>,
+    pub priority: LoadPriority,
+    /// The group this load shares a concurrency limit with, if any
+    pub(crate) load_group: Option<Arc<LoadGroup>>,
+}
+
+/// Loads that share a concurrency limit. Only some of the group's loads are sent to loader threads at first. The rest
+/// wait here, and each finished load starts the next one.
+#[derive(Debug, Default)]
+pub(crate) struct LoadGroup {
+    queued: Mutex<VecDeque<LoadRequest>>,
+}
+
+impl LoadGroup {
+    pub fn push(&self, load_request: LoadRequest) {
+        self.queued.lock().unwrap().push_back(load_request);
+    }
+
+    pub fn pop(&self) -> Option<LoadRequest> {
+        self.queued.lock().unwrap().pop_front()
+    }
+}
+
+/// Determines the order queued loads are handled in. Loader threads handle higher priority requests first.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum LoadPriority {
+    Low,
+    Normal,
+    High,
+}
+
+impl Default for LoadPriority {
+    fn default() -> Self {
+        LoadPriority::Normal
+    }
+}
+
+/// Determines how many times a load is retried when reading the asset fails with a (potentially) transient io error.
+/// [io::ErrorKind::NotFound] errors are never retried.
+#[derive(Debug, Copy, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: usize,
+    /// The delay before the first retry. Each subsequent retry waits one more `backoff` than the last.
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            backoff: Duration::from_millis(100),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Runs `read` until it succeeds, fails with a non-retryable error, or runs out of retries
+    pub fn retry<T>(&self, read: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+        self.retry_with(read, |err| Some(err))
+    }
+
+    /// Like [RetryPolicy::retry], but retries `load` when it fails with an [AssetLoadError::Io] error
+    pub fn retry_load<T>(
+        &self,
+        load: impl FnMut() -> Result<T, AssetLoadError>,
+    ) -> Result<T, AssetLoadError> {
+        self.retry_with(load, |err| match err {
+            AssetLoadError::Io(err) => Some(err),
+            _ => None,
+        })
+    }
+
+    fn retry_with<T, E: fmt::Debug>(
+        &self,
+        mut read: impl FnMut() -> Result<T, E>,
+        io_error: fn(&E) -> Option<&io::Error>,
+    ) -> Result<T, E> {
+        let mut retries = 0;
+        loop {
+            match read() {
+                Err(err)
+                    if retries < self.max_retries
+                        && io_error(&err)
+                            .is_some_and(|err| err.kind() != io::ErrorKind::NotFound) =>
+                {
+                    retries += 1;
+                    log::warn!("Retrying asset read after io error: {:?}", err);
+                    thread::sleep(self.backoff * retries as u32);
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// The function a [ByteTransform] runs on an asset's path and bytes
+pub type ByteTransformFn = dyn Fn(&Path, Vec<u8>) -> Result<Vec<u8>, AssetLoadError> + Send + Sync;
+
+/// Transforms an asset's raw bytes after they are read and before they are passed to its loader (ex: to decrypt them)
+#[derive(Clone)]
+pub struct ByteTransform(Arc<ByteTransformFn>);
+
+impl ByteTransform {
+    pub fn new(
+        transform: impl Fn(&Path, Vec<u8>) -> Result<Vec<u8>, AssetLoadError> + Send + Sync + 'static,
+    ) -> Self {
+        ByteTransform(Arc::new(transform))
+    }
+
+    pub fn apply(&self, path: &Path, bytes: Vec<u8>) -> Result<Vec<u8>, AssetLoadError> {
+        (self.0)(path, bytes)
+    }
+
+    /// Runs `load` with `byte_transform` applied to the bytes read by [read_asset_bytes](crate::read_asset_bytes) on
+    /// this thread
+    pub(crate) fn scope<T>(byte_transform: Option<&ByteTransform>, load: impl FnOnce() -> T) -> T {
+        struct Restore(Option<ByteTransform>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+                CURRENT_BYTE_TRANSFORM.with(|current| *current.borrow_mut() = previous);
+            }
+        }
+
+        let previous =
+            CURRENT_BYTE_TRANSFORM.with(|current| current.replace(byte_transform.cloned()));
+        let _restore = Restore(previous);
+        load()
+    }
+
+    /// Applies the byte transform of the load running on this thread, if any
+    pub(crate) fn apply_current(path: &Path, bytes: Vec<u8>) -> Result<Vec<u8>, AssetLoadError> {
+        let byte_transform = CURRENT_BYTE_TRANSFORM.with(|current| current.borrow().clone());
+        match byte_transform {
+            Some(byte_transform) => byte_transform.apply(path, bytes),
+            None => Ok(bytes),
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT_BYTE_TRANSFORM: RefCell<Option<ByteTransform>> = const { RefCell::new(None) };
+}
+
+impl fmt::Debug for ByteTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ByteTransform")
+    }
 }
 
 /// Handles load requests from an AssetServer
@@ -38,11 +194,11 @@ where
     }
 
     fn load_asset(&self, load_request: &LoadRequest) -> Result<TAsset, AssetLoadError> {
-        let mut file = File::open(&load_request.path)?;
-        let mut bytes = Vec::new();
-        file.read_to_end(&mut bytes)?;
-        let asset = self.loader.from_bytes(&load_request.path, bytes)?;
-        Ok(asset)
+        ByteTransform::scope(load_request.byte_transform.as_ref(), || {
+            load_request
+                .retry_policy
+                .retry_load(|| self.loader.load_from_file(&load_request.path))
+        })
     }
 }
 
@@ -59,12 +215,97 @@ where
             path: load_request.path.clone(),
             version: load_request.version,
         };
-        self.sender
-            .send(asset_result)
-            .expect("loaded asset should have been sent");
+        // the receiving AssetChannel is dropped when the app shuts down while loads are still in flight
+        if self.sender.send(asset_result).is_err() {
+            log::warn!(
+                "Dropped loaded asset {:?} because its asset channel no longer exists",
+                load_request.path
+            );
+        }
     }
 
     fn extensions(&self) -> &[&str] {
         self.loader.extensions()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{AssetLoadRequestHandler, ChannelAssetHandler, LoadRequest, RetryPolicy};
+    use crate::{AssetChannel, AssetLoadError, AssetLoader, HandleId};
+    use crossbeam_channel::Receiver;
+    use std::{io, path::Path, thread, time::Duration};
+
+    #[test]
+    fn retry_transient_errors() {
+        let policy = RetryPolicy {
+            max_retries: 3,
+            backoff: Duration::from_millis(1),
+        };
+
+        let mut attempts = 0;
+        let result = policy.retry(|| {
+            attempts += 1;
+            if attempts <= 2 {
+                Err(io::Error::new(io::ErrorKind::Interrupted, "transient"))
+            } else {
+                Ok(attempts)
+            }
+        });
+        assert_eq!(result.unwrap(), 3, "read succeeds on the third attempt");
+
+        let mut attempts = 0;
+        let result: io::Result<()> = policy.retry(|| {
+            attempts += 1;
+            Err(io::Error::new(io::ErrorKind::NotFound, "missing"))
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 1, "NotFound is not retried");
+    }
+
+    struct BlockingLoader(Receiver<()>);
+
+    impl AssetLoader<()> for BlockingLoader {
+        fn from_bytes(&self, _asset_path: &Path, _bytes: Vec<u8>) -> anyhow::Result<()> {
+            unreachable!("BlockingLoader overrides load_from_file")
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["blocking"]
+        }
+
+        fn load_from_file(&self, _asset_path: &Path) -> Result<(), AssetLoadError> {
+            self.0.recv().unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn finish_load_after_asset_channel_is_dropped() {
+        let (unblock, blocker) = crossbeam_channel::unbounded();
+        let asset_channel = AssetChannel::<()>::new();
+        let handler =
+            ChannelAssetHandler::new(BlockingLoader(blocker), asset_channel.sender.clone());
+
+        let load = thread::spawn(move || {
+            handler.handle_request(&LoadRequest {
+                path: "a.blocking".into(),
+                handle_id: HandleId::new(),
+                handler_index: 0,
+                version: 0,
+                retry_policy: RetryPolicy::default(),
+                byte_transform: None,
+                priority: Default::default(),
+                load_group: None,
+            })
+        });
+
+        // the app shuts down while the load is in flight
+        drop(asset_channel);
+        unblock.send(()).unwrap();
+        assert!(
+            load.join().is_ok(),
+            "loader threads don't panic when the asset channel is gone"
+        );
+    }
+}
@@ -1,9 +1,7 @@
-use crate::{AssetServer, AssetVersion, Assets, Handle, LoadState};
+use crate::{AssetServer, AssetVersion, Assets, ByteTransform, Handle, LoadState};
 use anyhow::Result;
-use bevy_ecs::{Res, ResMut, Resource};
+use bevy_ecs::{Commands, Component, Entity, Query, Res, ResMut, Resource};
 use crossbeam_channel::{Receiver, Sender, TryRecvError};
-use fs::File;
-use io::Read;
 use std::{
     fs, io,
     path::{Path, PathBuf},
@@ -24,14 +22,29 @@ pub trait AssetLoader<T>: Send + Sync + 'static {
     fn from_bytes(&self, asset_path: &Path, bytes: Vec<u8>) -> Result<T, anyhow::Error>;
     fn extensions(&self) -> &[&str];
     fn load_from_file(&self, asset_path: &Path) -> Result<T, AssetLoadError> {
-        let mut file = File::open(asset_path)?;
-        let mut bytes = Vec::new();
-        file.read_to_end(&mut bytes)?;
+        let bytes = read_asset_bytes(asset_path)?;
         let asset = self.from_bytes(asset_path, bytes)?;
         Ok(asset)
     }
 }
 
+/// Reads the asset file at `asset_path`, applying the loading [AssetServer]'s byte transform (if any)
+pub fn read_asset_bytes(asset_path: &Path) -> Result<Vec<u8>, AssetLoadError> {
+    let bytes = fs::read(asset_path)?;
+    ByteTransform::apply_current(asset_path, bytes)
+}
+
+/// Reads the file at `relative`, resolved against the directory containing `asset_path`. Loaders can use this to read
+/// files their asset references by relative path (ex: a GLTF file's `.bin` buffers). Like [read_asset_bytes], this
+/// applies the loading [AssetServer]'s byte transform (if any).
+pub fn read_sibling_bytes<P: AsRef<Path>>(asset_path: &Path, relative: P) -> io::Result<Vec<u8>> {
+    let directory = asset_path.parent().unwrap_or_else(|| Path::new(""));
+    read_asset_bytes(&directory.join(relative)).map_err(|err| match err {
+        AssetLoadError::Io(err) => err,
+        err => io::Error::new(io::ErrorKind::InvalidData, err),
+    })
+}
+
 /// The result of loading an asset of type `T`
 pub struct AssetResult<T: 'static> {
     pub result: Result<T, AssetLoadError>,
@@ -53,13 +66,15 @@ impl<T> AssetChannel<T> {
     }
 }
 
-/// Reads [AssetResult]s from an [AssetChannel] and updates the [Assets] collection and [LoadState] accordingly
+/// Reads [AssetResult]s from an [AssetChannel] and updates the [Assets] collection and [LoadState] accordingly. At most
+/// [AssetServer::commit_budget] results are read per call.
 pub fn update_asset_storage_system<T: Resource>(
     asset_channel: Res<AssetChannel<T>>,
     asset_server: Res<AssetServer>,
     mut assets: ResMut<Assets<T>>,
 ) {
-    loop {
+    let commit_budget = asset_server.commit_budget().unwrap_or(usize::MAX);
+    for _ in 0..commit_budget {
         match asset_channel.receiver.try_recv() {
             Ok(result) => match result.result {
                 Ok(asset) => {
@@ -80,3 +95,170 @@ pub fn update_asset_storage_system<T: Resource>(
         }
     }
 }
+
+/// Despawns entities whose `C` component references an asset that failed to load, so they don't linger with a
+/// placeholder forever
+pub fn despawn_on_failed_asset_system<T: Resource, C: Component + AsRef<Handle<T>>>(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut query: Query<(Entity, &C)>,
+) {
+    for (entity, component) in &mut query.iter() {
+        if let Some(LoadState::Failed(_)) = asset_server.get_load_state(*component.as_ref()) {
+            commands.despawn(entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        despawn_on_failed_asset_system, read_sibling_bytes, update_asset_storage_system,
+        AssetChannel, AssetLoader, AssetResult,
+    };
+    use crate::{AssetLoadRequestHandler, AssetServer, Assets, Handle, LoadRequest, LoadState};
+    use bevy_ecs::{IntoQuerySystem, Resources, Schedule, World};
+    use std::{env, fs, path::Path};
+
+    struct NoopHandler;
+
+    impl AssetLoadRequestHandler for NoopHandler {
+        fn handle_request(&self, _load_request: &LoadRequest) {}
+
+        fn extensions(&self) -> &[&str] {
+            &["noop"]
+        }
+    }
+
+    struct Sprite(Handle<String>);
+
+    impl AsRef<Handle<String>> for Sprite {
+        fn as_ref(&self) -> &Handle<String> {
+            &self.0
+        }
+    }
+
+    #[test]
+    fn despawn_on_failed_asset() {
+        let mut asset_server = AssetServer::default();
+        asset_server.add_handler(NoopHandler);
+        let loaded = asset_server.load::<String, _>("loaded.noop").unwrap();
+        let failed = asset_server.load::<String, _>("failed.noop").unwrap();
+        asset_server.set_load_state(loaded.id, LoadState::Loaded(0));
+        asset_server.set_load_state(failed.id, LoadState::Failed(0));
+
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(asset_server);
+        let loaded_entity = world.spawn((Sprite(loaded),));
+        let failed_entity = world.spawn((Sprite(failed),));
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage(
+            "update",
+            despawn_on_failed_asset_system::<String, Sprite>.system(),
+        );
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+
+        assert!(world.contains(loaded_entity));
+        assert!(!world.contains(failed_entity));
+    }
+
+    #[test]
+    fn sibling_bytes() {
+        let directory = env::temp_dir().join(format!("bevy_asset_sibling_{}", std::process::id()));
+        fs::create_dir_all(directory.join("buffers")).unwrap();
+        fs::write(directory.join("buffers").join("mesh.bin"), b"buffer").unwrap();
+
+        let asset_path = directory.join("mesh.gltf");
+        let bytes = read_sibling_bytes(&asset_path, "buffers/mesh.bin");
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(bytes.unwrap(), b"buffer");
+    }
+
+    struct SiblingLoader;
+
+    impl AssetLoader<Vec<u8>> for SiblingLoader {
+        fn from_bytes(&self, asset_path: &Path, mut bytes: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+            bytes.extend(read_sibling_bytes(asset_path, "sibling.bin")?);
+            Ok(bytes)
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["sibling"]
+        }
+    }
+
+    #[test]
+    fn sibling_bytes_are_transformed() {
+        const KEY: u8 = 0x5a;
+        let encrypt = |bytes: &[u8]| bytes.iter().map(|byte| byte ^ KEY).collect::<Vec<u8>>();
+        let directory = env::temp_dir().join(format!(
+            "bevy_asset_transformed_sibling_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("mesh.sibling"), encrypt(b"mesh")).unwrap();
+        fs::write(directory.join("sibling.bin"), encrypt(b"+buffer")).unwrap();
+
+        let mut asset_server = AssetServer::default();
+        asset_server.add_loader(SiblingLoader);
+        asset_server.set_byte_transform(|_path, bytes| {
+            Ok(bytes.into_iter().map(|byte| byte ^ KEY).collect())
+        });
+
+        let mut assets = Assets::<Vec<u8>>::default();
+        let handle = asset_server.load_sync(&mut assets, directory.join("mesh.sibling"));
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            assets.get(&handle.unwrap()).map(|bytes| bytes.as_slice()),
+            Some(&b"mesh+buffer"[..])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "commit budget must be at least one")]
+    fn zero_commit_budget() {
+        AssetServer::default().set_commit_budget(Some(0));
+    }
+
+    #[test]
+    fn commit_budget() {
+        let mut asset_server = AssetServer::default();
+        asset_server.set_commit_budget(Some(2));
+        let asset_channel = AssetChannel::<usize>::new();
+        for i in 0..5 {
+            asset_channel
+                .sender
+                .send(AssetResult {
+                    result: Ok(i),
+                    handle: Handle::new(),
+                    path: format!("{}.noop", i).into(),
+                    version: 0,
+                })
+                .unwrap();
+        }
+
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(asset_server);
+        resources.insert(asset_channel);
+        resources.insert(Assets::<usize>::default());
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("load_assets");
+        schedule.add_system_to_stage("load_assets", update_asset_storage_system::<usize>.system());
+        schedule.initialize(&mut resources);
+
+        let mut committed = Vec::new();
+        for _ in 0..3 {
+            schedule.run(&mut world, &mut resources);
+            committed.push(resources.get::<Assets<usize>>().unwrap().iter().count());
+        }
+        assert_eq!(committed, vec![2, 4, 5]);
+    }
+}
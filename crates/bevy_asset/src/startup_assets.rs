@@ -0,0 +1,171 @@
+use crate::{AssetServer, HandleId, LoadState};
+use bevy_app::AppBuilder;
+use bevy_ecs::{IntoQuerySystem, Res, ResMut};
+use std::path::{Path, PathBuf};
+
+/// Set to `true` once every asset passed to [LoadOnStartup::load_on_startup] has loaded. Assets that fail to load (or
+/// fail to start loading) keep this `false`.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct AssetsReady(pub bool);
+
+/// The assets passed to [LoadOnStartup::load_on_startup], along with their handles once they start loading
+#[derive(Debug, Default)]
+pub struct StartupAssets {
+    paths: Vec<PathBuf>,
+    handle_ids: Vec<HandleId>,
+    failed: bool,
+}
+
+impl StartupAssets {
+    pub fn handle_ids(&self) -> &[HandleId] {
+        &self.handle_ids
+    }
+
+    /// Returns true if a startup asset couldn't start loading, in which case [AssetsReady] never becomes `true`
+    pub fn failed(&self) -> bool {
+        self.failed
+    }
+}
+
+/// Starts loading every startup asset
+pub fn load_startup_assets_system(
+    asset_server: Res<AssetServer>,
+    mut startup_assets: ResMut<StartupAssets>,
+) {
+    let paths = std::mem::take(&mut startup_assets.paths);
+    for path in paths {
+        match asset_server.load_untyped(&path) {
+            Ok(handle_id) => startup_assets.handle_ids.push(handle_id),
+            Err(err) => {
+                log::error!("Failed to load startup asset {:?}: {:?}", path, err);
+                startup_assets.failed = true;
+            }
+        }
+    }
+}
+
+/// Flips [AssetsReady] to `true` once every startup asset has loaded
+pub fn startup_assets_ready_system(
+    asset_server: Res<AssetServer>,
+    startup_assets: Res<StartupAssets>,
+    mut assets_ready: ResMut<AssetsReady>,
+) {
+    if assets_ready.0 || startup_assets.failed {
+        return;
+    }
+
+    if let Some(LoadState::Loaded(_)) =
+        asset_server.get_group_load_state(&startup_assets.handle_ids)
+    {
+        assets_ready.0 = true;
+    }
+}
+
+/// [AppBuilder] extension methods for loading assets when the app starts
+pub trait LoadOnStartup {
+    /// Loads the assets at `paths` on startup. The [AssetsReady] resource becomes `true` once all of them have loaded,
+    /// so gameplay systems can wait on it.
+    fn load_on_startup<P: AsRef<Path>>(&mut self, paths: &[P]) -> &mut Self;
+}
+
+impl LoadOnStartup for AppBuilder {
+    fn load_on_startup<P: AsRef<Path>>(&mut self, paths: &[P]) -> &mut Self {
+        if !self.resources().contains::<StartupAssets>() {
+            self.init_resource::<StartupAssets>()
+                .init_resource::<AssetsReady>()
+                .add_startup_system(load_startup_assets_system.system())
+                .add_system_to_stage(
+                    bevy_app::stage::PRE_UPDATE,
+                    startup_assets_ready_system.system(),
+                );
+        }
+
+        self.resources_mut()
+            .get_mut::<StartupAssets>()
+            .unwrap()
+            .paths
+            .extend(paths.iter().map(|path| path.as_ref().to_owned()));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AssetsReady, LoadOnStartup, StartupAssets};
+    use crate::{AssetLoadRequestHandler, AssetServer, LoadRequest, LoadState};
+    use bevy_app::AppBuilder;
+    use bevy_ecs::Resources;
+
+    struct NoopHandler;
+
+    impl AssetLoadRequestHandler for NoopHandler {
+        fn handle_request(&self, _load_request: &LoadRequest) {}
+
+        fn extensions(&self) -> &[&str] {
+            &["noop"]
+        }
+    }
+
+    #[test]
+    fn assets_ready_after_startup_assets_load() {
+        let mut app_builder = AppBuilder::default();
+        let mut asset_server = AssetServer::default();
+        asset_server.add_handler(NoopHandler);
+        app_builder
+            .add_resource(asset_server)
+            .load_on_startup(&["a.noop", "b.noop"]);
+
+        let app = &mut app_builder.app;
+        app.startup_schedule.initialize(&mut app.resources);
+        app.startup_schedule.run(&mut app.world, &mut app.resources);
+        app.schedule.initialize(&mut app.resources);
+
+        let handle_ids = app
+            .resources
+            .get::<StartupAssets>()
+            .unwrap()
+            .handle_ids()
+            .to_vec();
+        assert_eq!(handle_ids.len(), 2);
+
+        let is_ready = |resources: &Resources| resources.get::<AssetsReady>().unwrap().0;
+        app.schedule.run(&mut app.world, &mut app.resources);
+        assert!(!is_ready(&app.resources));
+
+        let asset_server = app.resources.get::<AssetServer>().unwrap();
+        asset_server.set_load_state(handle_ids[0], LoadState::Loaded(0));
+        drop(asset_server);
+        app.schedule.run(&mut app.world, &mut app.resources);
+        assert!(
+            !is_ready(&app.resources),
+            "not ready while some assets are still loading"
+        );
+
+        let asset_server = app.resources.get::<AssetServer>().unwrap();
+        asset_server.set_load_state(handle_ids[1], LoadState::Loaded(0));
+        drop(asset_server);
+        app.schedule.run(&mut app.world, &mut app.resources);
+        assert!(is_ready(&app.resources));
+    }
+
+    #[test]
+    fn assets_never_ready_if_a_startup_asset_fails_to_load() {
+        let mut app_builder = AppBuilder::default();
+        let mut asset_server = AssetServer::default();
+        asset_server.add_handler(NoopHandler);
+        app_builder
+            .add_resource(asset_server)
+            .load_on_startup(&["unsupported.extension"]);
+
+        let app = &mut app_builder.app;
+        app.startup_schedule.initialize(&mut app.resources);
+        app.startup_schedule.run(&mut app.world, &mut app.resources);
+        app.schedule.initialize(&mut app.resources);
+        app.schedule.run(&mut app.world, &mut app.resources);
+
+        let startup_assets = app.resources.get::<StartupAssets>().unwrap();
+        assert!(startup_assets.failed());
+        assert!(startup_assets.handle_ids().is_empty());
+        assert!(!app.resources.get::<AssetsReady>().unwrap().0);
+    }
+}
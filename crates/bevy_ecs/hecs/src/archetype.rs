@@ -89,6 +89,11 @@ impl Archetype {
         self.len = 0;
     }
 
+    /// Empties the archetype without dropping its components. Their data must already have been moved elsewhere.
+    pub(crate) unsafe fn forget_all(&mut self) {
+        self.len = 0;
+    }
+
     #[allow(missing_docs)]
     #[inline]
     pub fn has<T: Component>(&self) -> bool {
@@ -368,8 +373,16 @@ impl Archetype {
         }
     }
 
-    /// Returns the ID of the entity moved into `index`, if any
-    pub(crate) unsafe fn move_to(
+    /// Removes the entity at `index` without dropping its components, passing each component to `f` to take
+    /// ownership of. Returns the ID of the entity moved into `index`, if any
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than [Archetype::len], and no borrow of any component in this archetype may be live.
+    /// `f` receives a pointer to each component's bytes along with its `TypeId` and size, and must move the component
+    /// out (ex: with `ptr::copy_nonoverlapping`) before returning. The component is never dropped by the archetype, and
+    /// its slot is overwritten once `f` returns, so the pointer must not be used afterwards.
+    pub unsafe fn move_to(
         &mut self,
         index: u32,
         mut f: impl FnMut(*mut u8, TypeId, usize, bool, bool),
@@ -84,7 +84,9 @@ pub use query::{
     Access, Added, BatchedIter, Changed, Mut, Mutated, Query, QueryBorrow, QueryIter, With, Without,
 };
 pub use query_one::QueryOne;
-pub use world::{ArchetypesGeneration, Component, ComponentError, Iter, SpawnBatchIter, World};
+pub use world::{
+    ArchetypesGeneration, Component, ComponentError, Iter, SpawnBatchIter, World, WorldId,
+};
 
 // Unstable implementation details needed by the macros
 #[doc(hidden)]
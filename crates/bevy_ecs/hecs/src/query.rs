@@ -15,12 +15,14 @@
 // modified by Bevy contributors
 
 use core::{
+    any::TypeId,
     marker::PhantomData,
     ops::{Deref, DerefMut},
     ptr::NonNull,
 };
 
 use crate::{archetype::Archetype, Component, Entity};
+use alloc::vec::Vec;
 
 /// A collection of component types to fetch from a `World`
 pub trait Query {
@@ -36,6 +38,9 @@ pub trait Fetch<'a>: Sized {
     /// How this query will access `archetype`, if at all
     fn access(archetype: &Archetype) -> Option<Access>;
 
+    /// Records the component types this fetch reads and writes, independent of any archetype
+    fn component_types(_reads: &mut Vec<TypeId>, _writes: &mut Vec<TypeId>) {}
+
     /// Acquire dynamic borrows from `archetype`
     fn borrow(archetype: &Archetype);
     /// Construct a `Fetch` for `archetype` if it should be traversed
@@ -126,6 +131,10 @@ impl<'a, T: Component> Fetch<'a> for FetchRead<T> {
         }
     }
 
+    fn component_types(reads: &mut Vec<TypeId>, _writes: &mut Vec<TypeId>) {
+        reads.push(TypeId::of::<T>());
+    }
+
     fn borrow(archetype: &Archetype) {
         archetype.borrow::<T>();
     }
@@ -205,6 +214,10 @@ impl<'a, T: Component> Fetch<'a> for FetchMut<T> {
         }
     }
 
+    fn component_types(_reads: &mut Vec<TypeId>, writes: &mut Vec<TypeId>) {
+        writes.push(TypeId::of::<T>());
+    }
+
     fn borrow(archetype: &Archetype) {
         archetype.borrow_mut::<T>();
     }
@@ -269,6 +282,10 @@ impl<'a, T: Component> Fetch<'a> for FetchMutated<T> {
         }
     }
 
+    fn component_types(reads: &mut Vec<TypeId>, _writes: &mut Vec<TypeId>) {
+        reads.push(TypeId::of::<T>());
+    }
+
     fn borrow(archetype: &Archetype) {
         archetype.borrow::<T>();
     }
@@ -334,6 +351,10 @@ impl<'a, T: Component> Fetch<'a> for FetchAdded<T> {
         }
     }
 
+    fn component_types(reads: &mut Vec<TypeId>, _writes: &mut Vec<TypeId>) {
+        reads.push(TypeId::of::<T>());
+    }
+
     fn borrow(archetype: &Archetype) {
         archetype.borrow::<T>();
     }
@@ -397,6 +418,10 @@ impl<'a, T: Component> Fetch<'a> for FetchChanged<T> {
         }
     }
 
+    fn component_types(reads: &mut Vec<TypeId>, _writes: &mut Vec<TypeId>) {
+        reads.push(TypeId::of::<T>());
+    }
+
     fn borrow(archetype: &Archetype) {
         archetype.borrow::<T>();
     }
@@ -442,6 +467,10 @@ impl<'a, T: Fetch<'a>> Fetch<'a> for TryFetch<T> {
         Some(T::access(archetype).unwrap_or(Access::Iterate))
     }
 
+    fn component_types(reads: &mut Vec<TypeId>, writes: &mut Vec<TypeId>) {
+        T::component_types(reads, writes)
+    }
+
     fn borrow(archetype: &Archetype) {
         T::borrow(archetype)
     }
@@ -500,6 +529,10 @@ impl<'a, T: Component, F: Fetch<'a>> Fetch<'a> for FetchWithout<T, F> {
         }
     }
 
+    fn component_types(reads: &mut Vec<TypeId>, writes: &mut Vec<TypeId>) {
+        F::component_types(reads, writes)
+    }
+
     fn borrow(archetype: &Archetype) {
         F::borrow(archetype)
     }
@@ -563,6 +596,10 @@ impl<'a, T: Component, F: Fetch<'a>> Fetch<'a> for FetchWith<T, F> {
         }
     }
 
+    fn component_types(reads: &mut Vec<TypeId>, writes: &mut Vec<TypeId>) {
+        F::component_types(reads, writes)
+    }
+
     fn borrow(archetype: &Archetype) {
         F::borrow(archetype)
     }
@@ -887,6 +924,11 @@ macro_rules! tuple_impl {
                 Some(access)
             }
 
+            #[allow(unused_variables)]
+            fn component_types(reads: &mut Vec<TypeId>, writes: &mut Vec<TypeId>) {
+                $($name::component_types(reads, writes);)*
+            }
+
             #[allow(unused_variables)]
             fn borrow(archetype: &Archetype) {
                 $($name::borrow(archetype);)*
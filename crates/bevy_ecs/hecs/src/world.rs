@@ -15,7 +15,12 @@
 // modified by Bevy contributors
 
 use crate::alloc::vec::Vec;
-use core::{any::TypeId, convert::TryFrom, fmt, mem, ptr};
+use core::{
+    any::TypeId,
+    convert::TryFrom,
+    fmt, mem, ptr,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 #[cfg(feature = "std")]
 use std::error::Error;
@@ -43,6 +48,7 @@ pub struct World {
     #[allow(missing_docs)]
     pub archetypes: Vec<Archetype>,
     archetype_generation: u64,
+    id: WorldId,
 }
 
 impl World {
@@ -59,9 +65,15 @@ impl World {
             archetypes,
             archetype_generation: 0,
             removed_components: HashMap::default(),
+            id: WorldId::new(),
         }
     }
 
+    /// Returns an id that is unique to this world
+    pub fn id(&self) -> WorldId {
+        self.id
+    }
+
     /// Create an entity with certain components
     ///
     /// Returns the ID of the newly created entity.
@@ -171,6 +183,30 @@ impl World {
         Ok(())
     }
 
+    /// Destroy every entity that matches the query `Q`, returning how many were destroyed
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// struct SceneTag;
+    /// let mut world = World::new();
+    /// world.spawn((SceneTag, 123));
+    /// let kept = world.spawn((456,));
+    /// assert_eq!(world.despawn_matching::<&SceneTag>(), 1);
+    /// assert!(world.contains(kept));
+    /// ```
+    pub fn despawn_matching<Q: Query>(&mut self) -> usize {
+        let entities = self
+            .query::<(Entity, Q)>()
+            .iter()
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        for entity in entities.iter() {
+            self.despawn(*entity).unwrap();
+        }
+        entities.len()
+    }
+
     /// Ensure `additional` entities with exact components `T` can be spawned without reallocating
     pub fn reserve<T: Bundle>(&mut self, additional: u32) {
         self.reserve_inner::<T>(additional);
@@ -210,6 +246,77 @@ impl World {
         self.entities.clear();
     }
 
+    /// Move all of `other`'s entities and their components into this world
+    ///
+    /// Entities keep their ids unless an id is already in use by this world, in which case the entity is given a
+    /// new id. Returns a map from each of `other`'s entities to its entity in this world, which can be used to
+    /// fix up components that reference other entities.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_hecs::*;
+    /// let mut world = World::new();
+    /// let mut other = World::new();
+    /// let a = other.spawn((123, "abc"));
+    /// let remap = world.merge(other);
+    /// assert_eq!(*world.get::<i32>(remap[&a]).unwrap(), 123);
+    /// ```
+    pub fn merge(&mut self, mut other: World) -> HashMap<Entity, Entity> {
+        let mut remap = HashMap::default();
+        remap.reserve(other.entities.entity_locations.len());
+        self.entities.reserve(
+            u32::try_from(other.entities.entity_locations.len()).expect("world too large"),
+        );
+        for source in other.archetypes.iter_mut() {
+            let ids = source.types().iter().map(|ty| ty.id()).collect::<Vec<_>>();
+            let archetype_id = match self.index.get(&ids).copied() {
+                Some(archetype_id) => archetype_id,
+                None => {
+                    let archetype_id = self.archetypes.len() as u32;
+                    self.archetypes
+                        .push(Archetype::new(source.types().to_vec()));
+                    self.index.insert(ids, archetype_id);
+                    self.archetype_generation += 1;
+                    archetype_id
+                }
+            };
+
+            let archetype = &mut self.archetypes[archetype_id as usize];
+            archetype.reserve(source.len());
+            for source_index in 0..source.len() {
+                let entity = Entity::from_id(source.entity_id(source_index));
+                let mut new_entity = entity;
+                while self.entities.contains(new_entity)
+                    || (new_entity != entity && other.entities.contains(new_entity))
+                {
+                    new_entity = Entity::new();
+                }
+
+                unsafe {
+                    let index = archetype.allocate(new_entity.id());
+                    for ty in source.types() {
+                        let size = ty.layout().size();
+                        let component = source.get_dynamic(ty.id(), size, source_index).unwrap();
+                        archetype.put_dynamic(component.as_ptr(), ty.id(), size, index, true);
+                    }
+                    self.entities.insert(
+                        new_entity,
+                        Location {
+                            archetype: archetype_id,
+                            index,
+                        },
+                    );
+                }
+                remap.insert(entity, new_entity);
+            }
+
+            // the components were moved into this world, so they must not be dropped with `other`
+            unsafe { source.forget_all() };
+        }
+
+        remap
+    }
+
     /// Whether `entity` still exists
     pub fn contains(&self, entity: Entity) -> bool {
         self.entities.contains(entity)
@@ -754,6 +861,18 @@ impl<A: DynamicBundle> core::iter::FromIterator<A> for World {
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct ArchetypesGeneration(pub u64);
 
+/// Identifies a `World`. Generations from different worlds aren't comparable, so information derived from
+/// `World::archetypes` should be cached alongside the id of the world it came from.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct WorldId(u64);
+
+impl WorldId {
+    fn new() -> Self {
+        static NEXT_WORLD_ID: AtomicU64 = AtomicU64::new(0);
+        WorldId(NEXT_WORLD_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
 /// Entity IDs created by `World::spawn_batch`
 pub struct SpawnBatchIter<'a, I>
 where
@@ -319,6 +319,41 @@ fn spawn_batch() {
     assert_eq!(entities.len(), 100);
 }
 
+#[test]
+fn spawn_batch_ids() {
+    let mut world = World::new();
+    let entities = world
+        .spawn_batch((0..100).map(|x| (x, "abc")))
+        .collect::<Vec<_>>();
+    assert_eq!(entities.len(), 100);
+
+    let unique = entities.iter().collect::<std::collections::HashSet<_>>();
+    assert_eq!(unique.len(), 100, "spawned entity ids are unique");
+    for (i, entity) in entities.iter().enumerate() {
+        assert!(world.contains(*entity));
+        assert_eq!(
+            *world.get::<i32>(*entity).unwrap(),
+            i as i32,
+            "ids are returned in spawn order"
+        );
+    }
+}
+
+#[test]
+fn despawn_matching() {
+    struct Tag;
+    let mut world = World::new();
+    let tagged = world.spawn((Tag, 123));
+    let tagged_other_archetype = world.spawn((Tag, "abc"));
+    let untagged = world.spawn((456,));
+
+    assert_eq!(world.despawn_matching::<&Tag>(), 2);
+    assert!(!world.contains(tagged));
+    assert!(!world.contains(tagged_other_archetype));
+    assert!(world.contains(untagged));
+    assert_eq!(world.despawn_matching::<&Tag>(), 0);
+}
+
 #[test]
 fn query_one() {
     let mut world = World::new();
@@ -404,3 +439,28 @@ fn remove_tracking() {
         "world clears result in 'removed component' states"
     );
 }
+
+#[test]
+fn merge_worlds() {
+    let mut world = World::new();
+    let existing = Entity::from_id(1);
+    world.spawn_as_entity(existing, ("existing", 0));
+
+    let mut other = World::new();
+    let conflicting = Entity::from_id(1);
+    other.spawn_as_entity(conflicting, ("conflicting", 1));
+    let other_entity = other.spawn(("other", true));
+
+    let remap = world.merge(other);
+    assert_eq!(remap.len(), 2);
+    assert_eq!(world.iter().count(), 3);
+
+    assert_ne!(
+        remap[&conflicting], existing,
+        "conflicting ids are remapped rather than overwritten"
+    );
+    assert_eq!(*world.get::<&str>(existing).unwrap(), "existing");
+    assert_eq!(*world.get::<i32>(remap[&conflicting]).unwrap(), 1);
+    assert_eq!(*world.get::<&str>(remap[&other_entity]).unwrap(), "other");
+    assert!(*world.get::<bool>(remap[&other_entity]).unwrap());
+}
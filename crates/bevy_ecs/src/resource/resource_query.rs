@@ -117,6 +117,55 @@ impl<'a, T: Resource + FromResources> DerefMut for Local<'a, T> {
     }
 }
 
+/// The value a resource had at the end of the previous frame. Kept up to date by [PreviousValue::update_system] and
+/// read through [ResHistory].
+pub struct PreviousValue<T: Resource + Clone>(T);
+
+impl<T: Resource + Clone> PreviousValue<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Snapshots the current value of `T`. Run this at the end of the frame so systems see last frame's value.
+    pub fn update_system(mut previous: ResMut<Self>, current: Res<T>) {
+        previous.0.clone_from(&current);
+    }
+}
+
+/// Shared borrow of a Resource and the value it had at the end of the previous frame. Requires a [PreviousValue]
+/// resource for `T`.
+pub struct ResHistory<'a, T: Resource + Clone> {
+    current: &'a T,
+    previous: &'a T,
+}
+
+impl<'a, T: Resource + Clone> ResHistory<'a, T> {
+    pub fn current(&self) -> &T {
+        self.current
+    }
+
+    pub fn previous(&self) -> &T {
+        self.previous
+    }
+}
+
+impl<'a, T: Resource + Clone> UnsafeClone for ResHistory<'a, T> {
+    unsafe fn unsafe_clone(&self) -> Self {
+        Self {
+            current: self.current,
+            previous: self.previous,
+        }
+    }
+}
+
+impl<'a, T: Resource + Clone> Deref for ResHistory<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.current
+    }
+}
+
 /// A collection of resource types fetch from a `Resources` collection
 pub trait ResourceQuery {
     type Fetch: for<'a> FetchResource<'a>;
@@ -235,6 +284,46 @@ impl<'a, T: Resource + FromResources> FetchResource<'a> for FetchResourceLocalMu
     }
 }
 
+impl<'a, T: Resource + Clone> ResourceQuery for ResHistory<'a, T> {
+    type Fetch = FetchResourceHistory<T>;
+}
+
+/// Fetches a resource reference along with its [PreviousValue]
+pub struct FetchResourceHistory<T>(PhantomData<T>);
+
+impl<'a, T: Resource + Clone> FetchResource<'a> for FetchResourceHistory<T> {
+    type Item = ResHistory<'a, T>;
+
+    unsafe fn get(resources: &'a Resources, _system_id: Option<SystemId>) -> Self::Item {
+        ResHistory {
+            current: &*resources
+                .get_unsafe_ref::<T>(ResourceIndex::Global)
+                .as_ptr(),
+            previous: &(*resources
+                .get_unsafe_ref::<PreviousValue<T>>(ResourceIndex::Global)
+                .as_ptr())
+            .0,
+        }
+    }
+
+    fn borrow(resources: &Resources) {
+        resources.borrow::<T>();
+        resources.borrow::<PreviousValue<T>>();
+    }
+
+    fn release(resources: &Resources) {
+        resources.release::<T>();
+        resources.release::<PreviousValue<T>>();
+    }
+
+    fn access() -> TypeAccess {
+        let mut access = TypeAccess::default();
+        access.immutable.insert(TypeId::of::<T>());
+        access.immutable.insert(TypeId::of::<PreviousValue<T>>());
+        access
+    }
+}
+
 macro_rules! tuple_impl {
     ($($name: ident),*) => {
         impl<'a, $($name: FetchResource<'a>),*> FetchResource<'a> for ($($name,)*) {
@@ -2,7 +2,7 @@ use super::{FetchResource, ResourceQuery};
 use crate::system::SystemId;
 use bevy_hecs::{Archetype, Ref, RefMut, TypeInfo};
 use core::any::TypeId;
-use std::{collections::HashMap, ptr::NonNull};
+use std::{collections::HashMap, mem::MaybeUninit, ptr::NonNull};
 
 /// A Resource type
 pub trait Resource: Send + Sync + 'static {}
@@ -30,6 +30,11 @@ impl Resources {
         self.insert_resource(resource, ResourceIndex::Global);
     }
 
+    /// Removes the resource of type `T` and returns it, if it exists
+    pub fn remove<T: Resource>(&mut self) -> Option<T> {
+        self.remove_resource(ResourceIndex::Global)
+    }
+
     pub fn contains<T: Resource>(&self) -> bool {
         self.get_resource::<T>(ResourceIndex::Global).is_some()
     }
@@ -101,6 +106,38 @@ impl Resources {
         }
     }
 
+    fn remove_resource<T: Resource>(&mut self, resource_index: ResourceIndex) -> Option<T> {
+        let data = self.resource_data.get_mut(&TypeId::of::<T>())?;
+        let index = match resource_index {
+            ResourceIndex::Global => data.default_index.take()?,
+            ResourceIndex::System(id) => data.system_id_to_archetype_index.remove(&id.0)?,
+        };
+
+        let last = data.archetype.len() - 1;
+        let mut resource = MaybeUninit::<T>::uninit();
+        unsafe {
+            data.archetype
+                .move_to(index, |ptr, _type_id, size, _is_added, _is_mutated| {
+                    std::ptr::copy_nonoverlapping(ptr, resource.as_mut_ptr().cast::<u8>(), size);
+                });
+        }
+
+        // the last resource was swapped into the removed resource's slot
+        if index != last {
+            if data.default_index == Some(last) {
+                data.default_index = Some(index);
+            } else if let Some(moved_index) = data
+                .system_id_to_archetype_index
+                .values_mut()
+                .find(|moved_index| **moved_index == last)
+            {
+                *moved_index = index;
+            }
+        }
+
+        Some(unsafe { resource.assume_init() })
+    }
+
     fn get_resource<T: Resource>(&self, resource_index: ResourceIndex) -> Option<Ref<'_, T>> {
         self.resource_data
             .get(&TypeId::of::<T>())
@@ -246,6 +283,35 @@ mod tests {
         assert_eq!(*resources.get::<i32>().expect("resource exists"), 123);
     }
 
+    #[test]
+    fn remove_resource() {
+        let mut resources = Resources::default();
+        resources.insert(123);
+        resources.insert_local(SystemId(0), 111);
+        resources.insert_local(SystemId(1), 222);
+
+        assert_eq!(resources.remove::<i32>(), Some(123));
+        assert!(resources.get::<i32>().is_none());
+        assert!(!resources.contains::<i32>());
+        assert_eq!(resources.remove::<i32>(), None);
+        assert_eq!(
+            *resources
+                .get_local::<i32>(SystemId(0))
+                .expect("resource exists"),
+            111,
+            "removing a resource doesn't affect local resources of the same type"
+        );
+        assert_eq!(
+            *resources
+                .get_local::<i32>(SystemId(1))
+                .expect("resource exists"),
+            222
+        );
+
+        resources.insert(456);
+        assert_eq!(*resources.get::<i32>().expect("resource exists"), 456);
+    }
+
     #[test]
     #[should_panic(expected = "i32 already borrowed")]
     fn resource_double_mut_panic() {
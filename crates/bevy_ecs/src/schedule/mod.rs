@@ -1,5 +1,7 @@
 mod parallel_executor;
 mod schedule;
+mod stage_builder;
 
 pub use parallel_executor::*;
 pub use schedule::*;
+pub use stage_builder::*;
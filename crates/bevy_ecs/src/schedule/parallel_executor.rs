@@ -1,7 +1,7 @@
 use super::Schedule;
 use crate::{
     resource::Resources,
-    system::{ArchetypeAccess, System, ThreadLocalExecution, TypeAccess},
+    system::{runs_before, ArchetypeAccess, System, ThreadLocalExecution, TypeAccess},
 };
 use bevy_hecs::{ArchetypesGeneration, World};
 use crossbeam_channel::{Receiver, Sender};
@@ -19,6 +19,7 @@ use std::{
 /// * in a given stage, systems the read archetype X cannot run before systems registered before them that write archetype X
 /// * in a given stage, systems that mutate resource Y cannot run before systems registered before them that read/write resource Y
 /// * in a given stage, systems the read resource Y cannot run before systems registered before them that write resource Y
+/// * in a given stage, systems cannot run before the systems they are ordered after with [SystemOrdering](crate::SystemOrdering)
 
 #[derive(Debug)]
 pub struct ParallelExecutor {
@@ -55,6 +56,10 @@ impl ParallelExecutor {
         }
         for (stage_name, executor_stage) in schedule.stage_order.iter().zip(self.stages.iter_mut())
         {
+            #[cfg(feature = "alloc_tracking")]
+            {
+                executor_stage.alloc_tracking = schedule.is_alloc_tracked(stage_name);
+            }
             if let Some(stage_systems) = schedule.stages.get_mut(stage_name) {
                 executor_stage.run(world, resources, stage_systems, schedule_changed);
             }
@@ -130,6 +135,9 @@ pub struct ExecutorStage {
     sender: Sender<usize>,
     receiver: Receiver<usize>,
     last_archetypes_generation: ArchetypesGeneration,
+    /// whether this stage reports each system run to the profiler. See [Schedule::with_alloc_tracking]
+    #[cfg(feature = "alloc_tracking")]
+    alloc_tracking: bool,
 }
 
 impl Default for ExecutorStage {
@@ -145,6 +153,8 @@ impl Default for ExecutorStage {
             sender,
             receiver,
             last_archetypes_generation: ArchetypesGeneration(u64::MAX), // MAX forces prepare to run the first time
+            #[cfg(feature = "alloc_tracking")]
+            alloc_tracking: false,
         }
     }
 }
@@ -239,6 +249,26 @@ impl ExecutorStage {
                             }
                         }
 
+                        // systems ordered with labels depend on the systems they run after, even if they don't conflict
+                        if !system.labels().is_empty() || !system.after_labels().is_empty() {
+                            for (earlier_system_index, earlier_system) in systems
+                                .iter()
+                                .enumerate()
+                                .take(system_index)
+                                .skip(prepare_system_index_range.start)
+                            {
+                                let earlier_system = earlier_system.lock().unwrap();
+                                if runs_before(&**earlier_system, &**system)
+                                    && !self.system_dependencies[system_index]
+                                        .contains(earlier_system_index)
+                                {
+                                    self.system_dependents[earlier_system_index].push(system_index);
+                                    self.system_dependencies[system_index]
+                                        .insert(earlier_system_index);
+                                }
+                            }
+                        }
+
                         current_archetype_access.union(archetype_access);
                         current_resource_access.union(resource_access);
 
@@ -310,10 +340,20 @@ impl ExecutorStage {
 
                 // handle multi-threaded system
                 let sender = self.sender.clone();
+                #[cfg(feature = "alloc_tracking")]
+                let alloc_tracking = self.alloc_tracking;
                 self.running_systems.insert(system_index);
                 scope.spawn_fifo(move |_| {
                     let mut system = system.lock().unwrap();
+                    #[cfg(feature = "alloc_tracking")]
+                    if alloc_tracking {
+                        crate::profiler_start_system(resources, system.id(), system.name().clone());
+                    }
                     system.run(world, resources);
+                    #[cfg(feature = "alloc_tracking")]
+                    if alloc_tracking {
+                        crate::profiler_stop_system(resources, system.id(), system.name().clone());
+                    }
                     sender.send(system_index).unwrap();
                 });
 
@@ -386,8 +426,16 @@ impl ExecutorStage {
                 // if a thread local system is ready to run, run it exclusively on the main thread
                 let mut system = systems[thread_local_index].lock().unwrap();
                 self.running_systems.insert(thread_local_index);
+                #[cfg(feature = "alloc_tracking")]
+                if self.alloc_tracking {
+                    crate::profiler_start_system(resources, system.id(), system.name().clone());
+                }
                 system.run(world, resources);
                 system.run_thread_local(world, resources);
+                #[cfg(feature = "alloc_tracking")]
+                if self.alloc_tracking {
+                    crate::profiler_stop_system(resources, system.id(), system.name().clone());
+                }
                 self.finished_systems.insert(thread_local_index);
                 self.sender.send(thread_local_index).unwrap();
 
@@ -1,14 +1,59 @@
 use crate::{
     resource::Resources,
     schedule::ParallelExecutorOptions,
-    system::{System, SystemId, ThreadLocalExecution},
+    system::{runs_before, System, SystemId, ThreadLocalExecution},
 };
 use bevy_hecs::World;
 use std::{
     borrow::Cow,
     collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
+use thiserror::Error;
+
+/// An error that occurs when running a [Schedule] stage
+#[derive(Error, Debug)]
+pub enum StageError {
+    #[error("Stage does not exist: {0}")]
+    NoSuchStage(String),
+}
+
+/// Min, max and mean durations measured over a number of runs
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TimingStats {
+    pub min: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+}
+
+impl TimingStats {
+    fn record(&mut self, run: usize, duration: Duration) {
+        if run == 0 || duration < self.min {
+            self.min = duration;
+        }
+        if duration > self.max {
+            self.max = duration;
+        }
+        // the running total is kept in `mean` and divided once all runs are recorded
+        self.mean += duration;
+    }
+
+    fn finish(&mut self, runs: usize) {
+        if runs > 0 {
+            self.mean /= runs as u32;
+        }
+    }
+}
+
+/// Timings collected by [Schedule::bench]
+#[derive(Debug, Clone)]
+pub struct ScheduleStats {
+    /// Timings for each stage, in the order the stages run
+    pub stages: Vec<(Cow<'static, str>, TimingStats)>,
+    /// Timings for a full run of the schedule
+    pub total: TimingStats,
+}
 
 /// An ordered collection of stages, which each contain an ordered list of [System]s.
 /// Schedules are essentially the "execution plan" for an App's systems.
@@ -20,16 +65,33 @@ pub struct Schedule {
     pub(crate) system_ids: HashSet<SystemId>,
     generation: usize,
     last_initialize_generation: usize,
+    /// cached by [Schedule::execution_plan] and cleared whenever a stage or system is added
+    execution_plan: Mutex<Option<Vec<SystemId>>>,
+    #[cfg(feature = "alloc_tracking")]
+    alloc_tracked_stages: HashSet<Cow<'static, str>>,
 }
 
 impl Schedule {
     pub fn add_stage(&mut self, stage: impl Into<Cow<'static, str>>) {
+        self.add_stage_with_capacity(stage, 0);
+    }
+
+    /// Adds a stage with storage preallocated for `capacity` systems. This avoids repeated reallocations when a
+    /// stage is known to hold many systems.
+    pub fn add_stage_with_capacity(
+        &mut self,
+        stage: impl Into<Cow<'static, str>>,
+        capacity: usize,
+    ) {
         let stage: Cow<str> = stage.into();
         if let Some(_) = self.stages.get(&stage) {
             panic!("Stage already exists: {}", stage);
         } else {
-            self.stages.insert(stage.clone(), Vec::new());
+            self.stages
+                .insert(stage.clone(), Vec::with_capacity(capacity));
             self.stage_order.push(stage);
+            self.invalidate_execution_plan();
+            self.system_ids.reserve(capacity);
         }
     }
 
@@ -54,6 +116,7 @@ impl Schedule {
 
         self.stages.insert(stage.clone(), Vec::new());
         self.stage_order.insert(target_index + 1, stage);
+        self.invalidate_execution_plan();
     }
 
     pub fn add_stage_before(
@@ -77,6 +140,7 @@ impl Schedule {
 
         self.stages.insert(stage.clone(), Vec::new());
         self.stage_order.insert(target_index, stage);
+        self.invalidate_execution_plan();
     }
 
     pub fn add_system_to_stage(
@@ -98,8 +162,10 @@ impl Schedule {
         }
         self.system_ids.insert(system.id());
         systems.push(Arc::new(Mutex::new(system)));
+        sort_stage_by_labels(&stage_name, systems);
 
         self.generation += 1;
+        self.invalidate_execution_plan();
         self
     }
 
@@ -122,46 +188,100 @@ impl Schedule {
         }
         self.system_ids.insert(system.id());
         systems.insert(0, Arc::new(Mutex::new(system)));
+        sort_stage_by_labels(&stage_name, systems);
 
         self.generation += 1;
+        self.invalidate_execution_plan();
+        self
+    }
+
+    /// Records the bytes each system in the stage allocates per run through the [Profiler](crate::Profiler)
+    /// resource. See [AllocationProfiler](crate::AllocationProfiler).
+    #[cfg(feature = "alloc_tracking")]
+    pub fn with_alloc_tracking(&mut self, stage_name: impl Into<Cow<'static, str>>) -> &mut Self {
+        let stage_name = stage_name.into();
+        if !self.stages.contains_key(&stage_name) {
+            panic!("Stage does not exist: {}", stage_name);
+        }
+        self.alloc_tracked_stages.insert(stage_name);
         self
     }
 
+    #[cfg(feature = "alloc_tracking")]
+    pub(crate) fn is_alloc_tracked(&self, stage_name: &str) -> bool {
+        self.alloc_tracked_stages.contains(stage_name)
+    }
+
+    #[cfg(not(feature = "alloc_tracking"))]
+    pub(crate) fn is_alloc_tracked(&self, _stage_name: &str) -> bool {
+        false
+    }
+
     pub fn run(&mut self, world: &mut World, resources: &mut Resources) {
         for stage_name in self.stage_order.iter() {
+            let alloc_tracking = self.is_alloc_tracked(stage_name);
             if let Some(stage_systems) = self.stages.get_mut(stage_name) {
-                for system in stage_systems.iter_mut() {
-                    let mut system = system.lock().unwrap();
-                    #[cfg(feature = "profiler")]
-                    crate::profiler_start(resources, system.name().clone());
-                    system.update_archetype_access(world);
-                    match system.thread_local_execution() {
-                        ThreadLocalExecution::NextFlush => system.run(world, resources),
-                        ThreadLocalExecution::Immediate => {
-                            system.run(world, resources);
-                            // NOTE: when this is made parallel a full sync is required here
-                            system.run_thread_local(world, resources);
-                        }
-                    }
-                    #[cfg(feature = "profiler")]
-                    crate::profiler_stop(resources, system.name().clone());
-                }
+                run_stage_systems(stage_systems, world, resources, alloc_tracking);
+            }
+        }
 
-                // "flush"
-                // NOTE: when this is made parallel a full sync is required here
-                for system in stage_systems.iter_mut() {
-                    let mut system = system.lock().unwrap();
-                    match system.thread_local_execution() {
-                        ThreadLocalExecution::NextFlush => {
-                            system.run_thread_local(world, resources)
-                        }
-                        ThreadLocalExecution::Immediate => { /* already ran immediate */ }
-                    }
+        world.clear_trackers();
+    }
+
+    /// Runs the schedule `iterations` times and measures how long each stage and each full run takes. Nothing is
+    /// allocated while measuring, so the stats reflect the systems' own overhead.
+    pub fn bench(
+        &mut self,
+        world: &mut World,
+        resources: &mut Resources,
+        iterations: usize,
+    ) -> ScheduleStats {
+        let mut stats = ScheduleStats {
+            stages: self
+                .stage_order
+                .iter()
+                .map(|stage_name| (stage_name.clone(), TimingStats::default()))
+                .collect(),
+            total: TimingStats::default(),
+        };
+
+        for iteration in 0..iterations {
+            let run_start = Instant::now();
+            for (stage_name, stage_stats) in stats.stages.iter_mut() {
+                let alloc_tracking = self.is_alloc_tracked(stage_name);
+                let stage_start = Instant::now();
+                if let Some(stage_systems) = self.stages.get_mut(stage_name) {
+                    run_stage_systems(stage_systems, world, resources, alloc_tracking);
                 }
+                stage_stats.record(iteration, stage_start.elapsed());
             }
+
+            world.clear_trackers();
+            stats.total.record(iteration, run_start.elapsed());
         }
 
-        world.clear_trackers();
+        for (_, stage_stats) in stats.stages.iter_mut() {
+            stage_stats.finish(iterations);
+        }
+        stats.total.finish(iterations);
+        stats
+    }
+
+    /// Runs only the systems in the stage with the given name. Unlike [Schedule::run], this does not clear the
+    /// world's change trackers afterwards.
+    pub fn run_stage(
+        &mut self,
+        stage_name: &str,
+        world: &mut World,
+        resources: &mut Resources,
+    ) -> Result<(), StageError> {
+        let alloc_tracking = self.is_alloc_tracked(stage_name);
+        let stage_systems = self
+            .stages
+            .get_mut(stage_name)
+            .ok_or_else(|| StageError::NoSuchStage(stage_name.to_string()))?;
+        run_stage_systems(stage_systems, world, resources, alloc_tracking);
+        Ok(())
     }
 
     // TODO: move this code to ParallelExecutor
@@ -192,4 +312,362 @@ impl Schedule {
     pub fn generation(&self) -> usize {
         self.generation
     }
+
+    /// Returns the ids of every system in the order [Schedule::run] executes them: stage by stage, and within a stage in
+    /// the order the systems were added
+    pub fn execution_plan(&self) -> Vec<SystemId> {
+        self.execution_plan
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| {
+                self.stage_order
+                    .iter()
+                    .flat_map(|stage_name| self.stages[stage_name].iter())
+                    .map(|system| system.lock().unwrap().id())
+                    .collect()
+            })
+            .clone()
+    }
+
+    fn invalidate_execution_plan(&mut self) {
+        *self.execution_plan.get_mut().unwrap() = None;
+    }
+
+    /// Renders the schedule as a Graphviz DOT graph. Each stage is a cluster containing its systems, and edges
+    /// follow the order stages run in.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph schedule {\n    compound=true;\n");
+        let mut previous_stage_node: Option<(usize, String)> = None;
+        for (stage_index, stage_name) in self.stage_order.iter().enumerate() {
+            dot.push_str(&format!(
+                "    subgraph cluster_{} {{\n        label=\"{}\";\n",
+                stage_index,
+                escape_dot(stage_name)
+            ));
+            let systems = &self.stages[stage_name];
+            for (system_index, system) in systems.iter().enumerate() {
+                let system = system.lock().unwrap();
+                dot.push_str(&format!(
+                    "        stage_{}_system_{} [label=\"{}\"];\n",
+                    stage_index,
+                    system_index,
+                    escape_dot(&system.name())
+                ));
+            }
+            // empty clusters aren't drawn, so give empty stages a placeholder node
+            if systems.is_empty() {
+                dot.push_str(&format!(
+                    "        stage_{}_empty [label=\"\", shape=point, style=invis];\n",
+                    stage_index
+                ));
+            }
+            dot.push_str("    }\n");
+
+            let stage_node = if systems.is_empty() {
+                format!("stage_{}_empty", stage_index)
+            } else {
+                format!("stage_{}_system_0", stage_index)
+            };
+            if let Some((previous_stage_index, previous_stage_node)) = previous_stage_node {
+                dot.push_str(&format!(
+                    "    {} -> {} [ltail=cluster_{}, lhead=cluster_{}];\n",
+                    previous_stage_node, stage_node, previous_stage_index, stage_index
+                ));
+            }
+            previous_stage_node = Some((stage_index, stage_node));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Reorders a stage's systems so each one comes after the systems its labels require it to run after. Otherwise
+/// systems keep the order they were added in.
+fn sort_stage_by_labels(stage_name: &str, systems: &mut Vec<Arc<Mutex<Box<dyn System>>>>) {
+    let order = {
+        let locked_systems = systems
+            .iter()
+            .map(|system| system.lock().unwrap())
+            .collect::<Vec<_>>();
+        if locked_systems
+            .iter()
+            .all(|system| system.before_labels().is_empty() && system.after_labels().is_empty())
+        {
+            return;
+        }
+
+        let mut remaining_dependencies = vec![0; systems.len()];
+        let mut dependents = vec![Vec::new(); systems.len()];
+        for (index, system) in locked_systems.iter().enumerate() {
+            for (other_index, other) in locked_systems.iter().enumerate() {
+                if index != other_index && runs_before(&***system, &***other) {
+                    dependents[index].push(other_index);
+                    remaining_dependencies[other_index] += 1;
+                }
+            }
+        }
+
+        let mut placed = vec![false; systems.len()];
+        let mut order = Vec::with_capacity(systems.len());
+        while order.len() < systems.len() {
+            // place the earliest added system that doesn't wait on an unplaced one
+            let next = (0..systems.len())
+                .find(|&index| !placed[index] && remaining_dependencies[index] == 0)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Systems in stage {} have cyclic before/after labels",
+                        stage_name
+                    )
+                });
+            placed[next] = true;
+            order.push(next);
+            for &dependent in dependents[next].iter() {
+                remaining_dependencies[dependent] -= 1;
+            }
+        }
+        order
+    };
+
+    let mut unsorted = systems.drain(..).map(Some).collect::<Vec<_>>();
+    systems.extend(
+        order
+            .into_iter()
+            .map(|index| unsorted[index].take().unwrap()),
+    );
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg_attr(not(feature = "alloc_tracking"), allow(unused_variables))]
+fn run_stage_systems(
+    stage_systems: &mut [Arc<Mutex<Box<dyn System>>>],
+    world: &mut World,
+    resources: &mut Resources,
+    alloc_tracking: bool,
+) {
+    for system in stage_systems.iter_mut() {
+        let mut system = system.lock().unwrap();
+        #[cfg(feature = "profiler")]
+        crate::profiler_start(resources, system.name().clone());
+        #[cfg(feature = "alloc_tracking")]
+        if alloc_tracking {
+            crate::profiler_start_system(resources, system.id(), system.name().clone());
+        }
+        system.update_archetype_access(world);
+        match system.thread_local_execution() {
+            ThreadLocalExecution::NextFlush => system.run(world, resources),
+            ThreadLocalExecution::Immediate => {
+                system.run(world, resources);
+                // NOTE: when this is made parallel a full sync is required here
+                system.run_thread_local(world, resources);
+            }
+        }
+        #[cfg(feature = "alloc_tracking")]
+        if alloc_tracking {
+            crate::profiler_stop_system(resources, system.id(), system.name().clone());
+        }
+        #[cfg(feature = "profiler")]
+        crate::profiler_stop(resources, system.name().clone());
+    }
+
+    // "flush"
+    // NOTE: when this is made parallel a full sync is required here
+    for system in stage_systems.iter_mut() {
+        let mut system = system.lock().unwrap();
+        match system.thread_local_execution() {
+            ThreadLocalExecution::NextFlush => system.run_thread_local(world, resources),
+            ThreadLocalExecution::Immediate => { /* already ran immediate */ }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Schedule, StageError, TimingStats};
+    use crate::{
+        resource::{ResMut, Resources},
+        system::{IntoQuerySystem, SystemOrdering},
+    };
+    use bevy_hecs::World;
+
+    #[test]
+    fn stage_with_capacity() {
+        fn noop() {}
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage_with_capacity("update", 16);
+        let capacity = schedule.stages["update"].capacity();
+        assert!(capacity >= 16);
+
+        for _ in 0..16 {
+            schedule.add_system_to_stage("update", noop.system());
+        }
+
+        assert_eq!(
+            schedule.stages["update"].capacity(),
+            capacity,
+            "adding systems up to the reserved capacity does not reallocate"
+        );
+    }
+
+    #[test]
+    fn run_single_stage() {
+        fn physics(mut log: ResMut<Vec<&'static str>>) {
+            log.push("physics");
+        }
+
+        fn render(mut log: ResMut<Vec<&'static str>>) {
+            log.push("render");
+        }
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(Vec::<&'static str>::new());
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("physics");
+        schedule.add_stage("render");
+        schedule.add_system_to_stage("physics", physics.system());
+        schedule.add_system_to_stage("render", render.system());
+        schedule.initialize(&mut resources);
+
+        schedule
+            .run_stage("physics", &mut world, &mut resources)
+            .unwrap();
+        assert_eq!(
+            *resources.get::<Vec<&'static str>>().unwrap(),
+            vec!["physics"]
+        );
+
+        match schedule.run_stage("missing", &mut world, &mut resources) {
+            Err(error @ StageError::NoSuchStage(_)) => {
+                assert_eq!(error.to_string(), "Stage does not exist: missing")
+            }
+            result => panic!("expected NoSuchStage, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn label_ordering() {
+        fn input() {}
+        fn physics() {}
+        fn render() {}
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        let render = render.system().after("physics");
+        let render_id = render.id();
+        let input = input.system().label("input").before("physics");
+        let input_id = input.id();
+        let physics = physics.system().label("physics");
+        let physics_id = physics.id();
+        schedule.add_system_to_stage("update", render);
+        schedule.add_system_to_stage("update", physics);
+        schedule.add_system_to_stage("update", input);
+
+        assert_eq!(
+            schedule.execution_plan(),
+            vec![input_id, physics_id, render_id]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cyclic before/after labels")]
+    fn cyclic_labels_panic() {
+        fn a() {}
+        fn b() {}
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", a.system().label("a").after("b"));
+        schedule.add_system_to_stage("update", b.system().label("b").after("a"));
+    }
+
+    #[test]
+    fn execution_plan() {
+        fn noop() {}
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_stage_before("update", "pre_update");
+        let systems = (0..4).map(|_| noop.system()).collect::<Vec<_>>();
+        let ids = systems.iter().map(|system| system.id()).collect::<Vec<_>>();
+        let mut systems = systems.into_iter();
+        schedule.add_system_to_stage("update", systems.next().unwrap());
+        schedule.add_system_to_stage("update", systems.next().unwrap());
+        schedule.add_system_to_stage("pre_update", systems.next().unwrap());
+        schedule.add_system_to_stage_front("update", systems.next().unwrap());
+
+        assert_eq!(
+            schedule.execution_plan(),
+            vec![ids[2], ids[3], ids[0], ids[1]]
+        );
+
+        // the cached plan is rebuilt after adding stages and systems
+        schedule.add_stage("post_update");
+        let post_update = noop.system();
+        let post_update_id = post_update.id();
+        schedule.add_system_to_stage("post_update", post_update);
+        assert_eq!(
+            schedule.execution_plan(),
+            vec![ids[2], ids[3], ids[0], ids[1], post_update_id]
+        );
+    }
+
+    #[test]
+    fn schedule_to_dot() {
+        fn physics() {}
+        fn render() {}
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("physics");
+        schedule.add_stage("render");
+        schedule.add_system_to_stage("physics", physics.system());
+        schedule.add_system_to_stage("render", render.system());
+
+        let dot = schedule.to_dot();
+        assert!(dot.starts_with("digraph schedule {"));
+        for name in &["physics", "render"] {
+            assert!(dot.contains(&format!("label=\"{}\"", name)));
+        }
+        for name in &[physics.system().name(), render.system().name()] {
+            assert!(dot.contains(name.as_ref()));
+        }
+        assert!(dot.contains("stage_0_system_0 -> stage_1_system_0"));
+    }
+
+    #[test]
+    fn bench_schedule() {
+        fn count(mut counter: ResMut<u32>) {
+            *counter += 1;
+        }
+
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(0u32);
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_stage("post_update");
+        schedule.add_system_to_stage("update", count.system());
+        schedule.initialize(&mut resources);
+
+        let stats = schedule.bench(&mut world, &mut resources, 1000);
+        assert_eq!(*resources.get::<u32>().unwrap(), 1000);
+        let stage_names = stats
+            .stages
+            .iter()
+            .map(|(name, _)| name.as_ref())
+            .collect::<Vec<_>>();
+        assert_eq!(stage_names, vec!["update", "post_update"]);
+        for (_, stage_stats) in stats.stages.iter() {
+            assert!(stage_stats.min <= stage_stats.mean && stage_stats.mean <= stage_stats.max);
+        }
+        assert!(stats.total.min <= stats.total.mean && stats.total.mean <= stats.total.max);
+
+        let stats = schedule.bench(&mut world, &mut resources, 0);
+        assert_eq!(stats.total, TimingStats::default());
+    }
 }
@@ -0,0 +1,81 @@
+use super::Schedule;
+use crate::system::System;
+use std::borrow::Cow;
+
+/// Records the systems of a stage as constructors, so the same stage configuration can be
+/// instantiated any number of times.
+///
+/// Boxed [System]s can't be cloned, so each [SystemStageBuilder::build] call constructs fresh systems
+/// with their own [SystemId](crate::SystemId) and state. This is useful for building several similar stages
+/// (ex: one pipeline per player) without re-adding the same systems by hand.
+#[derive(Default)]
+pub struct SystemStageBuilder {
+    system_constructors: Vec<Box<dyn Fn() -> Box<dyn System> + Send + Sync>>,
+}
+
+impl SystemStageBuilder {
+    pub fn add_system(
+        &mut self,
+        constructor: impl Fn() -> Box<dyn System> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.system_constructors.push(Box::new(constructor));
+        self
+    }
+
+    /// Constructs a new instance of every recorded system, in the order they were added
+    pub fn build(&self) -> Vec<Box<dyn System>> {
+        self.system_constructors
+            .iter()
+            .map(|constructor| constructor())
+            .collect()
+    }
+
+    /// Adds a new stage with the given name to `schedule` and fills it with fresh instances of the recorded systems
+    pub fn build_stage(&self, schedule: &mut Schedule, stage_name: impl Into<Cow<'static, str>>) {
+        let stage_name = stage_name.into();
+        schedule.add_stage(stage_name.clone());
+        for system in self.build() {
+            schedule.add_system_to_stage(stage_name.clone(), system);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SystemStageBuilder;
+    use crate::{
+        resource::{Local, ResMut, Resources},
+        schedule::Schedule,
+        system::IntoQuerySystem,
+    };
+    use bevy_hecs::World;
+
+    #[test]
+    fn build_independent_stages() {
+        fn count(mut runs: Local<usize>, mut log: ResMut<Vec<usize>>) {
+            *runs += 1;
+            log.push(*runs);
+        }
+
+        let mut builder = SystemStageBuilder::default();
+        builder.add_system(|| count.system());
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(Vec::<usize>::new());
+
+        let mut schedule = Schedule::default();
+        builder.build_stage(&mut schedule, "player_1");
+        builder.build_stage(&mut schedule, "player_2");
+
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+        schedule.run(&mut world, &mut resources);
+
+        assert_eq!(
+            *resources.get::<Vec<usize>>().unwrap(),
+            vec![1, 1, 2, 2],
+            "each built stage runs its own copy of the system with separate local state"
+        );
+    }
+}
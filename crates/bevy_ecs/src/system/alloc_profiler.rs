@@ -0,0 +1,186 @@
+use super::{Profiler, SystemId};
+use std::{
+    alloc::{GlobalAlloc, Layout, System as SystemAllocator},
+    borrow::Cow,
+    cell::Cell,
+    collections::HashMap,
+    sync::RwLock,
+};
+
+thread_local! {
+    static ALLOCATED_BYTES: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A global allocator that counts the bytes allocated by each thread.
+///
+/// Install it with `#[global_allocator]` to let an [AllocationProfiler] attribute allocations to systems.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count_allocation(layout.size());
+        SystemAllocator.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count_allocation(layout.size());
+        SystemAllocator.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count_allocation(new_size.saturating_sub(layout.size()));
+        SystemAllocator.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        SystemAllocator.dealloc(ptr, layout)
+    }
+}
+
+fn count_allocation(size: usize) {
+    // the thread local may already be destroyed during thread shutdown
+    let _ = ALLOCATED_BYTES.try_with(|bytes| bytes.set(bytes.get().wrapping_add(size)));
+}
+
+/// The total number of bytes the current thread has allocated through the [CountingAllocator]
+pub fn allocated_bytes() -> usize {
+    ALLOCATED_BYTES.with(|bytes| bytes.get())
+}
+
+/// A [Profiler] that records how many bytes each system allocates per run.
+///
+/// This requires [CountingAllocator] to be installed as the global allocator. Otherwise every system reports zero bytes.
+/// Only systems in stages enabled with [Schedule::with_alloc_tracking](crate::Schedule::with_alloc_tracking) are recorded.
+#[derive(Default)]
+pub struct AllocationProfiler {
+    run_starts: RwLock<HashMap<SystemId, usize>>,
+    alloc_stats: RwLock<HashMap<SystemId, usize>>,
+}
+
+impl AllocationProfiler {
+    /// Returns the bytes allocated by each system during its most recent run
+    pub fn get_alloc_stats(&self) -> HashMap<SystemId, usize> {
+        self.alloc_stats.read().unwrap().clone()
+    }
+}
+
+impl Profiler for AllocationProfiler {
+    fn start(&self, _scope: Cow<'static, str>) {}
+
+    fn stop(&self, _scope: Cow<'static, str>) {}
+
+    fn start_system(&self, id: SystemId, _name: Cow<'static, str>) {
+        let mut run_starts = self.run_starts.write().unwrap();
+        // insert before reading the counter so the profiler's own allocation isn't attributed to the system
+        let run_start = run_starts.entry(id).or_insert(0);
+        *run_start = allocated_bytes();
+    }
+
+    fn stop_system(&self, id: SystemId, _name: Cow<'static, str>) {
+        let run_end = allocated_bytes();
+        let run_start = match self.run_starts.read().unwrap().get(&id) {
+            Some(run_start) => *run_start,
+            None => return,
+        };
+        self.alloc_stats
+            .write()
+            .unwrap()
+            .insert(id, run_end.wrapping_sub(run_start));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AllocationProfiler, CountingAllocator};
+    use crate::{
+        resource::Resources,
+        schedule::{ParallelExecutor, Schedule},
+        system::{IntoQuerySystem, Profiler, SystemId},
+    };
+    use bevy_hecs::World;
+    use std::collections::HashMap;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn allocating_system() {
+        let bytes = vec![0u8; 1024];
+        assert_eq!(bytes.len(), 1024);
+    }
+
+    fn empty_system() {}
+
+    fn alloc_stats(resources: &Resources) -> HashMap<SystemId, usize> {
+        let profiler = resources.get::<Box<dyn Profiler>>().unwrap();
+        profiler
+            .downcast_ref::<AllocationProfiler>()
+            .unwrap()
+            .get_alloc_stats()
+    }
+
+    #[test]
+    fn system_allocations() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert::<Box<dyn Profiler>>(Box::new(AllocationProfiler::default()));
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        let allocating = allocating_system.system();
+        let allocating_id = allocating.id();
+        let empty = empty_system.system();
+        let empty_id = empty.id();
+        schedule.add_system_to_stage("update", allocating);
+        schedule.add_system_to_stage("update", empty);
+        schedule.with_alloc_tracking("update");
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+
+        let stats = alloc_stats(&resources);
+        assert!(stats[&allocating_id] >= 1024);
+        assert_eq!(stats[&empty_id], 0);
+    }
+
+    #[test]
+    fn untracked_stages_are_not_recorded() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert::<Box<dyn Profiler>>(Box::new(AllocationProfiler::default()));
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", allocating_system.system());
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+        ParallelExecutor::default().run(&mut schedule, &mut world, &mut resources);
+
+        assert!(alloc_stats(&resources).is_empty());
+    }
+
+    #[test]
+    fn parallel_executor_records_allocations() {
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert::<Box<dyn Profiler>>(Box::new(AllocationProfiler::default()));
+
+        // two instances of the same system function share a name, but are recorded separately
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        let first = allocating_system.system();
+        let first_id = first.id();
+        let second = allocating_system.system();
+        let second_id = second.id();
+        schedule.add_system_to_stage("update", first);
+        schedule.add_system_to_stage("update", second);
+        schedule.with_alloc_tracking("update");
+        schedule.initialize(&mut resources);
+
+        let mut executor = ParallelExecutor::default();
+        executor.run(&mut schedule, &mut world, &mut resources);
+
+        let stats = alloc_stats(&resources);
+        assert_eq!(stats.len(), 2);
+        assert!(stats[&first_id] >= 1024);
+        assert!(stats[&second_id] >= 1024);
+    }
+}
@@ -4,7 +4,7 @@ use crate::{
     resource::{FetchResource, ResourceQuery, Resources, UnsafeClone},
     system::{ArchetypeAccess, Commands, System, SystemId, ThreadLocalExecution},
 };
-use bevy_hecs::{Fetch, Query as HecsQuery, World};
+use bevy_hecs::{ArchetypesGeneration, Fetch, Query as HecsQuery, World, WorldId};
 use std::borrow::Cow;
 
 pub(crate) struct SystemFn<State, F, ThreadLocalF, Init, SetArchetypeAccess>
@@ -21,10 +21,14 @@ where
     pub init_func: Init,
     pub thread_local_execution: ThreadLocalExecution,
     pub resource_access: TypeAccess,
+    pub component_access: TypeAccess,
     pub name: Cow<'static, str>,
     pub id: SystemId,
     pub archetype_access: ArchetypeAccess,
     pub set_archetype_access: SetArchetypeAccess,
+    pub last_archetypes_generation: ArchetypesGeneration,
+    /// the world `last_archetypes_generation` was read from
+    pub last_world_id: Option<WorldId>,
 }
 
 impl<State, F, ThreadLocalF, Init, SetArchetypeAccess> System
@@ -41,7 +45,16 @@ where
     }
 
     fn update_archetype_access(&mut self, world: &World) {
+        // archetype access only changes when new archetypes are created, or when running on another world
+        if self.last_world_id == Some(world.id())
+            && self.last_archetypes_generation == world.archetypes_generation()
+        {
+            return;
+        }
+
         (self.set_archetype_access)(world, &mut self.archetype_access, &mut self.state);
+        self.last_archetypes_generation = world.archetypes_generation();
+        self.last_world_id = Some(world.id());
     }
 
     fn archetype_access(&self) -> &ArchetypeAccess {
@@ -52,6 +65,10 @@ where
         &self.resource_access
     }
 
+    fn component_access(&self) -> &TypeAccess {
+        &self.component_access
+    }
+
     fn thread_local_execution(&self) -> ThreadLocalExecution {
         self.thread_local_execution
     }
@@ -98,6 +115,8 @@ macro_rules! impl_into_foreach_system {
             #[allow(unused_unsafe)]
             fn system(mut self) -> Box<dyn System> {
                 let id = SystemId::new();
+                let mut component_access = TypeAccess::default();
+                component_access.set_access_for_query::<($($component,)*)>();
                 Box::new(SystemFn {
                     state: Commands::default(),
                     thread_local_execution: ThreadLocalExecution::NextFlush,
@@ -120,7 +139,10 @@ macro_rules! impl_into_foreach_system {
                         <($($resource,)*)>::initialize(resources, Some(id));
                     },
                     resource_access: <<($($resource,)*) as ResourceQuery>::Fetch as FetchResource>::access(),
+                    component_access,
                     archetype_access: ArchetypeAccess::default(),
+                    last_archetypes_generation: ArchetypesGeneration(u64::MAX), // MAX forces an update the first time
+                    last_world_id: None,
                     set_archetype_access: |world, archetype_access, _state| {
                         archetype_access.clear();
                         archetype_access.set_access_for_query::<($($component,)*)>(world);
@@ -161,6 +183,8 @@ macro_rules! impl_into_query_system {
             #[allow(unused_mut)]
             fn system(mut self) -> Box<dyn System> {
                 let id = SystemId::new();
+                let mut component_access = TypeAccess::default();
+                $(component_access.set_access_for_query::<$query>();)*
                 $(let $query = ArchetypeAccess::default();)*
                 Box::new(SystemFn {
                     state: QuerySystemState {
@@ -194,7 +218,10 @@ macro_rules! impl_into_query_system {
                         <($($resource,)*)>::initialize(resources, Some(id));
                     },
                     resource_access: <<($($resource,)*) as ResourceQuery>::Fetch as FetchResource>::access(),
+                    component_access,
                     archetype_access: ArchetypeAccess::default(),
+                    last_archetypes_generation: ArchetypesGeneration(u64::MAX), // MAX forces an update the first time
+                    last_world_id: None,
                     set_archetype_access: |world, archetype_access, state| {
                         archetype_access.clear();
                         let mut i = 0;
@@ -321,7 +348,10 @@ where
             name: core::any::type_name::<F>().into(),
             id: SystemId::new(),
             resource_access: TypeAccess::default(),
+            component_access: TypeAccess::default(),
             archetype_access: ArchetypeAccess::default(),
+            last_archetypes_generation: ArchetypesGeneration(u64::MAX), // MAX forces an update the first time
+            last_world_id: None,
         })
     }
 }
@@ -342,12 +372,14 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::{IntoQuerySystem, Query};
+    use super::{IntoQuerySystem, Query, SystemFn};
+    use crate::system::{ArchetypeAccess, System, SystemId, ThreadLocalExecution, TypeAccess};
     use crate::{
-        resource::{ResMut, Resources},
+        resource::{FromResources, Local, PreviousValue, Res, ResHistory, ResMut, Resources},
         schedule::Schedule,
     };
-    use bevy_hecs::{Entity, With, World};
+    use bevy_hecs::{ArchetypesGeneration, Entity, Mutated, With, World};
+    use std::any::TypeId;
 
     struct A;
     struct B;
@@ -415,4 +447,311 @@ mod tests {
 
         assert!(*resources.get::<bool>().unwrap(), "system ran");
     }
+
+    #[test]
+    fn query_system_component_access() {
+        fn query_system(_a_query: Query<&A>, _b_query: Query<With<C, &mut B>>) {}
+
+        let system = query_system.system();
+        let access = system.component_access();
+        assert_eq!(
+            access.immutable.iter().cloned().collect::<Vec<TypeId>>(),
+            vec![TypeId::of::<A>()]
+        );
+        assert_eq!(
+            access.mutable.iter().cloned().collect::<Vec<TypeId>>(),
+            vec![TypeId::of::<B>()],
+            "With<C, _> filters on C without accessing it"
+        );
+        assert_eq!(
+            system.component_access_types(),
+            (vec![TypeId::of::<A>()], vec![TypeId::of::<B>()])
+        );
+    }
+
+    #[test]
+    fn disjoint_mutable_queries() {
+        struct Position(u32);
+        struct Velocity(u32);
+
+        fn mutate_both(mut positions: Query<&mut Position>, mut velocities: Query<&mut Velocity>) {
+            // both queries borrow the same archetype, but different component columns
+            let mut position_borrow = positions.iter();
+            let mut velocity_borrow = velocities.iter();
+            for (mut position, mut velocity) in position_borrow.iter().zip(velocity_borrow.iter()) {
+                position.0 += 1;
+                velocity.0 += 1;
+            }
+        }
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        let entity = world.spawn((Position(0), Velocity(0)));
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", mutate_both.system());
+        schedule.run(&mut world, &mut resources);
+
+        assert_eq!(world.get::<Position>(entity).unwrap().0, 1);
+        assert_eq!(world.get::<Velocity>(entity).unwrap().0, 1);
+    }
+
+    #[test]
+    fn query_get_mut_marks_mutated() {
+        struct Health(u32);
+
+        fn damage(target: Res<Entity>, health: Query<&mut Health>) {
+            health.get_mut::<Health>(*target).unwrap().0 -= 1;
+        }
+
+        fn record_mutated(mut log: ResMut<Vec<u32>>, mut health: Query<Mutated<Health>>) {
+            for health in &mut health.iter() {
+                log.push(health.0);
+            }
+        }
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(Vec::<u32>::new());
+        world.spawn((Health(10),));
+        let target = world.spawn((Health(10),));
+        resources.insert(target);
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("damage");
+        schedule.add_stage("record");
+        schedule.add_system_to_stage("damage", damage.system());
+        schedule.add_system_to_stage("record", record_mutated.system());
+        schedule.run(&mut world, &mut resources);
+
+        assert_eq!(
+            *resources.get::<Vec<u32>>().unwrap(),
+            vec![9],
+            "only the entity written through get_mut is mutated"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "requires a query that writes")]
+    fn query_for_each_pair_mut_requires_write_access() {
+        struct Position;
+        struct Velocity(i32);
+
+        fn collide(mut query: Query<(&mut Position, &Velocity)>) {
+            query.for_each_pair_mut::<Velocity>(|(_, a), (_, b)| {
+                a.0 += 1;
+                b.0 += 1;
+            });
+        }
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        world.spawn((Position, Velocity(0)));
+        world.spawn((Position, Velocity(0)));
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", collide.system());
+        schedule.run(&mut world, &mut resources);
+    }
+
+    #[test]
+    fn query_for_each_pair_mut() {
+        struct Position(i32);
+        struct Velocity;
+
+        fn collide(mut log: ResMut<Vec<(Entity, Entity)>>, mut positions: Query<&mut Position>) {
+            positions.for_each_pair_mut::<Position>(|(a, a_position), (b, b_position)| {
+                assert_ne!(a, b, "an entity is never paired with itself");
+                a_position.0 += 1;
+                b_position.0 += 1;
+                log.push((a, b));
+            });
+        }
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(Vec::<(Entity, Entity)>::new());
+        let entities = vec![
+            world.spawn((Position(0),)),
+            world.spawn((Position(0),)),
+            world.spawn((Position(0), Velocity)),
+            world.spawn((Position(0), Velocity)),
+        ];
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", collide.system());
+        schedule.run(&mut world, &mut resources);
+
+        let mut pairs = resources
+            .get::<Vec<(Entity, Entity)>>()
+            .unwrap()
+            .iter()
+            .map(|&(a, b)| if a < b { (a, b) } else { (b, a) })
+            .collect::<Vec<_>>();
+        pairs.sort();
+        pairs.dedup();
+        assert_eq!(pairs.len(), 6, "every unique pair is visited exactly once");
+        for entity in entities {
+            assert_eq!(
+                world.get::<Position>(entity).unwrap().0,
+                3,
+                "each entity is in a pair with every other entity"
+            );
+        }
+    }
+
+    #[test]
+    fn archetype_access_updates_only_on_new_archetypes() {
+        let mut system = SystemFn {
+            state: 0usize,
+            func: |_, _, _, _| {},
+            thread_local_func: |_, _, _| {},
+            init_func: |_| {},
+            set_archetype_access: |_, _, updates: &mut usize| *updates += 1,
+            thread_local_execution: ThreadLocalExecution::NextFlush,
+            name: "counting".into(),
+            id: SystemId::new(),
+            resource_access: TypeAccess::default(),
+            component_access: TypeAccess::default(),
+            archetype_access: ArchetypeAccess::default(),
+            last_archetypes_generation: ArchetypesGeneration(u64::MAX),
+            last_world_id: None,
+        };
+
+        let mut world = World::default();
+        world.spawn((A,));
+        system.update_archetype_access(&world);
+        system.update_archetype_access(&world);
+        assert_eq!(
+            system.state, 1,
+            "unchanged archetypes don't trigger an update"
+        );
+
+        world.spawn((A,));
+        system.update_archetype_access(&world);
+        assert_eq!(
+            system.state, 1,
+            "spawning into an existing archetype doesn't trigger an update"
+        );
+
+        world.spawn((A, B));
+        system.update_archetype_access(&world);
+        assert_eq!(system.state, 2, "new archetypes trigger an update");
+
+        // a fresh world can share the previous world's generation while laying out its archetypes differently
+        let mut other_world = World::default();
+        other_world.spawn((B,));
+        other_world.spawn((A, B));
+        assert_eq!(
+            other_world.archetypes_generation(),
+            world.archetypes_generation()
+        );
+        system.update_archetype_access(&other_world);
+        assert_eq!(system.state, 3, "switching worlds triggers an update");
+    }
+
+    #[test]
+    fn query_iter_sorted_by() {
+        struct Id(u32);
+
+        fn collect_ids(mut log: ResMut<Vec<u32>>, mut ids: Query<&Id>) {
+            for id in ids.iter().iter_sorted_by(|id| id.0) {
+                log.push(id.0);
+            }
+        }
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(Vec::<u32>::new());
+        world.spawn((Id(3), A));
+        world.spawn((Id(1),));
+        world.spawn((Id(4), B));
+        world.spawn((Id(0), A));
+        world.spawn((Id(2),));
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", collect_ids.system());
+        schedule.run(&mut world, &mut resources);
+
+        assert_eq!(*resources.get::<Vec<u32>>().unwrap(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn local_resources_are_scoped_per_system() {
+        struct Counter(usize);
+
+        impl FromResources for Counter {
+            fn from_resources(resources: &Resources) -> Self {
+                Counter(*resources.get::<usize>().unwrap())
+            }
+        }
+
+        fn count(mut counter: Local<Counter>, mut log: ResMut<Vec<usize>>) {
+            counter.0 += 1;
+            log.push(counter.0);
+        }
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(10usize);
+        resources.insert(Vec::<usize>::new());
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", count.system());
+        schedule.add_system_to_stage("update", count.system());
+
+        schedule.initialize(&mut resources);
+        schedule.run(&mut world, &mut resources);
+        *resources.get_mut::<usize>().unwrap() = 100;
+        schedule.run(&mut world, &mut resources);
+
+        assert_eq!(
+            *resources.get::<Vec<usize>>().unwrap(),
+            vec![11, 11, 12, 12],
+            "each system instance initializes its own Local<T> once, using FromResources"
+        );
+        assert!(
+            !resources.contains::<Counter>(),
+            "Local<T> resources are not visible as global resources"
+        );
+    }
+
+    #[test]
+    fn res_history_lags_by_one_frame() {
+        fn increment(mut value: ResMut<u32>) {
+            *value += 1;
+        }
+
+        fn record(value: ResHistory<u32>, mut log: ResMut<Vec<(u32, u32)>>) {
+            log.push((*value.current(), *value.previous()));
+        }
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        resources.insert(0u32);
+        resources.insert(PreviousValue::new(0u32));
+        resources.insert(Vec::<(u32, u32)>::new());
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_stage("last");
+        schedule.add_system_to_stage("update", increment.system());
+        schedule.add_system_to_stage("update", record.system());
+        schedule.add_system_to_stage("last", PreviousValue::<u32>::update_system.system());
+
+        schedule.initialize(&mut resources);
+        for _ in 0..3 {
+            schedule.run(&mut world, &mut resources);
+        }
+
+        assert_eq!(
+            *resources.get::<Vec<(u32, u32)>>().unwrap(),
+            vec![(1, 0), (2, 1), (3, 2)]
+        );
+    }
 }
@@ -1,12 +1,19 @@
+#[cfg(feature = "alloc_tracking")]
+mod alloc_profiler;
 mod commands;
 mod into_system;
+mod ordering;
 #[cfg(feature = "profiler")]
 mod profiler;
 mod query;
 mod system;
 
+#[cfg(feature = "alloc_tracking")]
+pub use alloc_profiler::*;
 pub use commands::*;
 pub use into_system::*;
+pub(crate) use ordering::runs_before;
+pub use ordering::SystemOrdering;
 #[cfg(feature = "profiler")]
 pub use profiler::*;
 pub use query::*;
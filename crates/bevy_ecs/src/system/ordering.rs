@@ -0,0 +1,134 @@
+use super::{ArchetypeAccess, System, SystemId, ThreadLocalExecution, TypeAccess};
+use crate::resource::Resources;
+use bevy_hecs::World;
+use std::borrow::Cow;
+
+/// Labels a [System] and orders it against other labeled systems in the same stage, so plugins can order their systems
+/// without sharing [SystemId]s.
+///
+/// ## Example
+/// ```
+/// use bevy_ecs::{IntoQuerySystem, Schedule, SystemOrdering};
+///
+/// fn physics() {}
+/// fn collisions() {}
+///
+/// let mut schedule = Schedule::default();
+/// schedule.add_stage("update");
+/// schedule.add_system_to_stage("update", collisions.system().after("physics"));
+/// schedule.add_system_to_stage("update", physics.system().label("physics"));
+/// ```
+pub trait SystemOrdering {
+    /// Adds a label that other systems can reference with [SystemOrdering::before] and [SystemOrdering::after]
+    fn label(self, label: impl Into<Cow<'static, str>>) -> Box<dyn System>;
+    /// Runs this system before every system in the same stage with the given label
+    fn before(self, label: impl Into<Cow<'static, str>>) -> Box<dyn System>;
+    /// Runs this system after every system in the same stage with the given label
+    fn after(self, label: impl Into<Cow<'static, str>>) -> Box<dyn System>;
+}
+
+impl SystemOrdering for Box<dyn System> {
+    fn label(self, label: impl Into<Cow<'static, str>>) -> Box<dyn System> {
+        let mut system = OrderedSystem::new(self);
+        system.labels.push(label.into());
+        Box::new(system)
+    }
+
+    fn before(self, label: impl Into<Cow<'static, str>>) -> Box<dyn System> {
+        let mut system = OrderedSystem::new(self);
+        system.before.push(label.into());
+        Box::new(system)
+    }
+
+    fn after(self, label: impl Into<Cow<'static, str>>) -> Box<dyn System> {
+        let mut system = OrderedSystem::new(self);
+        system.after.push(label.into());
+        Box::new(system)
+    }
+}
+
+/// Wraps a [System] with the labels added through [SystemOrdering]
+struct OrderedSystem {
+    system: Box<dyn System>,
+    labels: Vec<Cow<'static, str>>,
+    before: Vec<Cow<'static, str>>,
+    after: Vec<Cow<'static, str>>,
+}
+
+impl OrderedSystem {
+    fn new(system: Box<dyn System>) -> Self {
+        // copy the wrapped system's labels so chained calls keep all of them
+        OrderedSystem {
+            labels: system.labels().to_vec(),
+            before: system.before_labels().to_vec(),
+            after: system.after_labels().to_vec(),
+            system,
+        }
+    }
+}
+
+impl System for OrderedSystem {
+    fn name(&self) -> Cow<'static, str> {
+        self.system.name()
+    }
+
+    fn id(&self) -> SystemId {
+        self.system.id()
+    }
+
+    fn update_archetype_access(&mut self, world: &World) {
+        self.system.update_archetype_access(world);
+    }
+
+    fn archetype_access(&self) -> &ArchetypeAccess {
+        self.system.archetype_access()
+    }
+
+    fn resource_access(&self) -> &TypeAccess {
+        self.system.resource_access()
+    }
+
+    fn component_access(&self) -> &TypeAccess {
+        self.system.component_access()
+    }
+
+    fn labels(&self) -> &[Cow<'static, str>] {
+        &self.labels
+    }
+
+    fn before_labels(&self) -> &[Cow<'static, str>] {
+        &self.before
+    }
+
+    fn after_labels(&self) -> &[Cow<'static, str>] {
+        &self.after
+    }
+
+    fn thread_local_execution(&self) -> ThreadLocalExecution {
+        self.system.thread_local_execution()
+    }
+
+    fn run(&mut self, world: &World, resources: &Resources) {
+        self.system.run(world, resources);
+    }
+
+    fn run_thread_local(&mut self, world: &mut World, resources: &mut Resources) {
+        self.system.run_thread_local(world, resources);
+    }
+
+    fn initialize(&mut self, resources: &mut Resources) {
+        self.system.initialize(resources);
+    }
+}
+
+/// Returns true if `system` has to run before `other` because of their labels
+pub(crate) fn runs_before(system: &dyn System, other: &dyn System) -> bool {
+    system
+        .before_labels()
+        .iter()
+        .any(|label| other.labels().contains(label))
+        || other
+            .after_labels()
+            .iter()
+            .any(|label| system.labels().contains(label))
+}
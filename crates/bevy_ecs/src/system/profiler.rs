@@ -1,4 +1,4 @@
-use crate::Resources;
+use crate::{Resources, SystemId};
 use downcast_rs::{impl_downcast, Downcast};
 use std::borrow::Cow;
 
@@ -8,6 +8,13 @@ use std::borrow::Cow;
 pub trait Profiler: Downcast + Send + Sync + 'static {
     fn start(&self, scope: Cow<'static, str>);
     fn stop(&self, scope: Cow<'static, str>);
+
+    /// Runs at the start of each run of the system `id` in stages with allocation tracking enabled. See
+    /// `Schedule::with_alloc_tracking`.
+    fn start_system(&self, _id: SystemId, _name: Cow<'static, str>) {}
+
+    /// Runs at the end of each run of the system `id` in stages with allocation tracking enabled.
+    fn stop_system(&self, _id: SystemId, _name: Cow<'static, str>) {}
 }
 
 pub fn profiler_start(resources: &Resources, scope: Cow<'static, str>) {
@@ -22,4 +29,16 @@ pub fn profiler_stop(resources: &Resources, scope: Cow<'static, str>) {
     }
 }
 
+pub fn profiler_start_system(resources: &Resources, id: SystemId, name: Cow<'static, str>) {
+    if let Some(profiler) = resources.get::<Box<dyn Profiler>>() {
+        profiler.start_system(id, name);
+    }
+}
+
+pub fn profiler_stop_system(resources: &Resources, id: SystemId, name: Cow<'static, str>) {
+    if let Some(profiler) = resources.get::<Box<dyn Profiler>>() {
+        profiler.stop_system(id, name);
+    }
+}
+
 impl_downcast!(Profiler);
@@ -3,7 +3,7 @@ use bevy_hecs::{
     Archetype, Component, ComponentError, Entity, Fetch, Query as HecsQuery, QueryOne, Ref, RefMut,
     World,
 };
-use std::marker::PhantomData;
+use std::{any::TypeId, marker::PhantomData};
 
 /// Provides scoped access to a World according to a given [HecsQuery]
 pub struct Query<'a, Q: HecsQuery> {
@@ -82,6 +82,9 @@ impl<'a, Q: HecsQuery> Query<'a, Q> {
 
     /// Gets a mutable reference to the entity's component of the given type. This will fail if the entity does not have
     /// the given component type or if the given component type does not match this query.
+    ///
+    /// Writing through the returned reference flags the component as mutated, so [Mutated](bevy_hecs::Mutated) queries
+    /// will match the entity until the world's trackers are cleared.
     pub fn get_mut<T: Component>(&self, entity: Entity) -> Result<RefMut<'_, T>, QueryError> {
         if let Some(location) = self.world.get_entity_location(entity) {
             if self
@@ -100,6 +103,63 @@ impl<'a, Q: HecsQuery> Query<'a, Q> {
         }
     }
 
+    /// Calls `f` once for every unordered pair of distinct entities that have a `T` component this query can write.
+    /// Both components of a pair can be mutated at once (ex: to resolve a collision), and each pair is visited exactly
+    /// once. Components passed to `f` are flagged as mutated.
+    ///
+    /// This visits `n * (n - 1) / 2` pairs, so it is best suited to small sets of entities. Panics if the query doesn't
+    /// write `T`.
+    pub fn for_each_pair_mut<T: Component>(
+        &mut self,
+        mut f: impl FnMut((Entity, &mut T), (Entity, &mut T)),
+    ) {
+        // the archetype access only says the query writes *something* in each archetype, so check that it writes T
+        let mut reads = Vec::new();
+        let mut writes = Vec::new();
+        <Q::Fetch as Fetch>::component_types(&mut reads, &mut writes);
+        if !writes.contains(&TypeId::of::<T>()) {
+            panic!(
+                "for_each_pair_mut::<{}> requires a query that writes {0}",
+                std::any::type_name::<T>()
+            );
+        }
+
+        let archetypes = &self.world.archetypes;
+        let mut components = Vec::new();
+        for index in self.archetype_access.mutable.ones() {
+            let archetype = &archetypes[index];
+            if let Some((data, mutated)) = archetype.get_with_mutated::<T>() {
+                archetype.borrow_mut::<T>();
+                for (i, id) in archetype.iter_entities().enumerate() {
+                    // SAFE: i is less than the archetype's length
+                    unsafe {
+                        components.push((
+                            Entity::from_id(*id),
+                            data.as_ptr().add(i),
+                            mutated.as_ptr().add(i),
+                        ));
+                    }
+                }
+            }
+        }
+
+        for (i, &(a, a_component, a_mutated)) in components.iter().enumerate() {
+            for &(b, b_component, b_mutated) in components[i + 1..].iter() {
+                // SAFE: every entity has its own component slot, so a and b never alias. the archetypes are borrowed
+                // uniquely until all pairs have been visited
+                unsafe {
+                    *a_mutated = true;
+                    *b_mutated = true;
+                    f((a, &mut *a_component), (b, &mut *b_component));
+                }
+            }
+        }
+
+        for index in self.archetype_access.mutable.ones() {
+            archetypes[index].release_mut::<T>();
+        }
+    }
+
     pub fn removed<C: Component>(&self) -> &[Entity] {
         self.world.removed::<C>()
     }
@@ -149,6 +209,21 @@ impl<'w, Q: HecsQuery> QueryBorrow<'w, Q> {
             iter: None,
         }
     }
+
+    /// Collects every match of the query and yields them sorted by `key`. Entities with equal keys keep their
+    /// archetype order.
+    ///
+    /// Archetype iteration order depends on the order archetypes were created in, so this is meant for
+    /// determinism-sensitive code (ex: reproducible simulations or snapshot tests). It allocates on every call,
+    /// so prefer [QueryBorrow::iter] in hot loops.
+    pub fn iter_sorted_by<'q, K: Ord>(
+        &'q mut self,
+        mut key: impl FnMut(&<Q::Fetch as Fetch<'q>>::Item) -> K,
+    ) -> std::vec::IntoIter<<Q::Fetch as Fetch<'q>>::Item> {
+        let mut items = self.iter().collect::<Vec<_>>();
+        items.sort_by_key(|item| key(item));
+        items.into_iter()
+    }
 }
 
 unsafe impl<'w, Q: HecsQuery> Send for QueryBorrow<'w, Q> {}
@@ -1,7 +1,7 @@
 use crate::resource::Resources;
-use bevy_hecs::{Access, Query, World};
+use bevy_hecs::{Access, Fetch, Query, World};
 use fixedbitset::FixedBitSet;
-use std::{any::TypeId, borrow::Cow, collections::HashSet};
+use std::{any::TypeId, borrow::Cow, collections::HashSet, sync::OnceLock};
 
 /// Determines the strategy used to run the `run_thread_local` function in a [System]
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -26,6 +26,32 @@ pub trait System: Send + Sync {
     fn update_archetype_access(&mut self, world: &World);
     fn archetype_access(&self) -> &ArchetypeAccess;
     fn resource_access(&self) -> &TypeAccess;
+    /// The component types this system's queries read and write. Unlike [System::archetype_access], this is known
+    /// before the system ever sees a [World]. Systems that don't track this report an empty access.
+    fn component_access(&self) -> &TypeAccess {
+        static EMPTY: OnceLock<TypeAccess> = OnceLock::new();
+        EMPTY.get_or_init(TypeAccess::default)
+    }
+    /// The component types this system reads and writes, as `(reads, writes)`
+    fn component_access_types(&self) -> (Vec<TypeId>, Vec<TypeId>) {
+        let access = self.component_access();
+        (
+            access.immutable.iter().cloned().collect(),
+            access.mutable.iter().cloned().collect(),
+        )
+    }
+    /// Labels other systems in the same stage can be ordered against. See [SystemOrdering](crate::SystemOrdering)
+    fn labels(&self) -> &[Cow<'static, str>] {
+        &[]
+    }
+    /// Labels of the systems in the same stage that this system runs before
+    fn before_labels(&self) -> &[Cow<'static, str>] {
+        &[]
+    }
+    /// Labels of the systems in the same stage that this system runs after
+    fn after_labels(&self) -> &[Cow<'static, str>] {
+        &[]
+    }
     fn thread_local_execution(&self) -> ThreadLocalExecution;
     fn run(&mut self, world: &World, resources: &Resources);
     fn run_thread_local(&mut self, world: &mut World, resources: &mut Resources);
@@ -95,6 +121,17 @@ impl TypeAccess {
         self.immutable.extend(&other.immutable);
     }
 
+    pub fn set_access_for_query<Q>(&mut self)
+    where
+        Q: Query,
+    {
+        let mut reads = Vec::new();
+        let mut writes = Vec::new();
+        <Q::Fetch as Fetch>::component_types(&mut reads, &mut writes);
+        self.immutable.extend(reads);
+        self.mutable.extend(writes);
+    }
+
     pub fn clear(&mut self) {
         self.immutable.clear();
         self.mutable.clear();
@@ -103,10 +140,10 @@ impl TypeAccess {
 
 #[cfg(test)]
 mod tests {
-    use super::{ArchetypeAccess, TypeAccess};
-    use crate::resource::{FetchResource, Res, ResMut, ResourceQuery};
+    use super::{ArchetypeAccess, System, SystemId, ThreadLocalExecution, TypeAccess};
+    use crate::resource::{FetchResource, Res, ResMut, ResourceQuery, Resources};
     use bevy_hecs::World;
-    use std::any::TypeId;
+    use std::{any::TypeId, borrow::Cow};
 
     struct A;
     struct B;
@@ -148,4 +185,34 @@ mod tests {
         expected_access.mutable.insert(TypeId::of::<B>());
         assert_eq!(access, expected_access);
     }
+
+    #[test]
+    fn default_component_access_is_empty() {
+        struct NoQueries(ArchetypeAccess, TypeAccess);
+
+        impl System for NoQueries {
+            fn name(&self) -> Cow<'static, str> {
+                "no_queries".into()
+            }
+            fn id(&self) -> SystemId {
+                SystemId(0)
+            }
+            fn update_archetype_access(&mut self, _world: &World) {}
+            fn archetype_access(&self) -> &ArchetypeAccess {
+                &self.0
+            }
+            fn resource_access(&self) -> &TypeAccess {
+                &self.1
+            }
+            fn thread_local_execution(&self) -> ThreadLocalExecution {
+                ThreadLocalExecution::NextFlush
+            }
+            fn run(&mut self, _world: &World, _resources: &Resources) {}
+            fn run_thread_local(&mut self, _world: &mut World, _resources: &mut Resources) {}
+        }
+
+        let system = NoQueries(ArchetypeAccess::default(), TypeAccess::default());
+        assert_eq!(system.component_access(), &TypeAccess::default());
+        assert_eq!(system.component_access_types(), (Vec::new(), Vec::new()));
+    }
 }
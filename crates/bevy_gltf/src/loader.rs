@@ -4,9 +4,9 @@ use bevy_render::{
 };
 
 use anyhow::Result;
-use bevy_asset::AssetLoader;
+use bevy_asset::{read_sibling_bytes, AssetLoader};
 use gltf::{buffer::Source, iter, mesh::Mode};
-use std::{fs, io, path::Path};
+use std::{io, path::Path};
 use thiserror::Error;
 
 /// Loads meshes from GLTF files into Mesh assets
@@ -128,8 +128,7 @@ fn load_buffers(buffers: iter::Buffers, asset_path: &Path) -> Result<Vec<Vec<u8>
                         return Err(GltfError::BufferFormatUnsupported);
                     }
                 } else {
-                    let buffer_path = asset_path.parent().unwrap().join(uri);
-                    let buffer_bytes = fs::read(buffer_path)?;
+                    let buffer_bytes = read_sibling_bytes(asset_path, uri)?;
                     buffer_data.push(buffer_bytes);
                 }
             }
@@ -1,6 +1,6 @@
-use crate::serde::SceneSerializer;
+use crate::{serde::SceneSerializer, SceneSpawnError};
 use anyhow::Result;
-use bevy_ecs::World;
+use bevy_ecs::{Resources, World};
 use bevy_property::{DynamicProperties, PropertyTypeRegistry};
 use bevy_type_registry::ComponentRegistry;
 use serde::Serialize;
@@ -43,6 +43,42 @@ impl Scene {
         scene
     }
 
+    /// Replaces every entity in `world` with the entities in this scene, keeping their ids. Together with
+    /// [Scene::from_world] this snapshots and restores a world's registered components (ex: for undo or rollback).
+    ///
+    /// The world is left untouched if the scene contains an unregistered component.
+    pub fn restore_world(
+        &self,
+        world: &mut World,
+        resources: &Resources,
+        component_registry: &ComponentRegistry,
+    ) -> Result<(), SceneSpawnError> {
+        let mut registrations = Vec::new();
+        for scene_entity in self.entities.iter() {
+            for component in scene_entity.components.iter() {
+                let component_registration = component_registry
+                    .get_with_name(&component.type_name)
+                    .ok_or_else(|| SceneSpawnError::UnregisteredComponent {
+                        type_name: component.type_name.to_string(),
+                    })?;
+                registrations.push(component_registration);
+            }
+        }
+
+        world.clear();
+        let mut registrations = registrations.drain(..);
+        for scene_entity in self.entities.iter() {
+            let entity = bevy_ecs::Entity::from_id(scene_entity.entity);
+            world.spawn_as_entity(entity, ());
+            for component in scene_entity.components.iter() {
+                let component_registration = registrations.next().unwrap();
+                component_registration.add_component_to_entity(world, resources, entity, component);
+            }
+        }
+
+        Ok(())
+    }
+
     // TODO: move to AssetSaver when it is implemented
     pub fn serialize_ron(
         &self,
@@ -65,3 +101,48 @@ where
     serialize.serialize(&mut ron_serializer)?;
     Ok(String::from_utf8(buf).unwrap())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Scene;
+    use bevy_ecs::{Entity, Resources, World};
+    use bevy_property::Properties;
+    use bevy_type_registry::ComponentRegistry;
+
+    #[derive(Properties, Default, Debug, PartialEq)]
+    struct Health {
+        value: u32,
+    }
+
+    #[test]
+    fn snapshot_and_restore_world() {
+        let mut component_registry = ComponentRegistry::default();
+        component_registry.register::<Health>();
+        let resources = Resources::default();
+
+        let mut world = World::new();
+        let a = world.spawn((Health { value: 10 },));
+        let b = world.spawn((Health { value: 20 },));
+        let snapshot = Scene::from_world(&world, &component_registry);
+
+        world.get_mut::<Health>(a).unwrap().value = 0;
+        world.despawn(b).unwrap();
+        let c = world.spawn((Health { value: 30 },));
+
+        snapshot
+            .restore_world(&mut world, &resources, &component_registry)
+            .unwrap();
+
+        assert!(
+            !world.contains(c),
+            "entities spawned after the snapshot are removed"
+        );
+        let mut restored = world
+            .query::<(Entity, &Health)>()
+            .iter()
+            .map(|(entity, health)| (entity, health.value))
+            .collect::<Vec<_>>();
+        restored.sort_by_key(|(_, value)| *value);
+        assert_eq!(restored, vec![(a, 10), (b, 20)]);
+    }
+}